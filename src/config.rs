@@ -0,0 +1,655 @@
+use crate::*;
+use num_traits::FromPrimitive;
+
+/// FIFO configuration,
+/// see `FIFO_CTRL_REG`
+#[derive(Copy, Clone)]
+pub struct FifoConfig {
+    pub mode: FifoMode,
+    pub trigger: InterruptGenerator, // trigger event of `StreamToFifo` (`TR`)
+    pub watermark: u8,               // 0-31 samples before the watermark flag is set (`FTH`)
+}
+
+impl FifoConfig {
+    /// `FIFO_CTRL_REG` register value
+    pub(crate) fn bits(&self) -> u8 {
+        let tr = match self.trigger {
+            InterruptGenerator::Int1 => 0,
+            InterruptGenerator::Int2 => TR,
+        };
+        ((self.mode as u8) << 6) | tr | (self.watermark & FTH_MASK)
+    }
+
+    /// Decoded from the `FIFO_CTRL_REG` register value
+    pub(crate) fn from_bits(v: u8) -> Option<Self> {
+        Some(Self {
+            mode: FifoMode::from_u8(v >> 6)?,
+            trigger: if (v & TR) != 0 {
+                InterruptGenerator::Int2
+            } else {
+                InterruptGenerator::Int1
+            },
+            watermark: v & FTH_MASK,
+        })
+    }
+}
+
+impl Default for FifoConfig {
+    fn default() -> Self {
+        Self {
+            mode: FifoMode::Stream,
+            trigger: InterruptGenerator::Int1,
+            watermark: 16,
+        }
+    }
+}
+
+/// Click detection of a `Config`,
+/// the click signal is routed and latched through the `InterruptRouting` of the config
+#[derive(Copy, Clone)]
+pub struct ClickDetection {
+    pub source: ClickSource,
+    pub xyz_axes_enabled: (bool, bool, bool), // (x, y, z)
+    pub threshold_mg: u16,                    // force applied to tap (< full scale)
+    pub time: ClickTime,
+}
+
+impl ClickDetection {
+    /// `CLICK_CFG` register value
+    pub(crate) fn cfg_bits(&self) -> u8 {
+        let (x, y, z) = self.xyz_axes_enabled;
+        let (xb, yb, zb) = match self.source {
+            ClickSource::Single => (XS, YS, ZS),
+            ClickSource::Double => (XD, YD, ZD),
+        };
+        let mut v = 0;
+        v |= if x { xb } else { 0 };
+        v |= if y { yb } else { 0 };
+        v |= if z { zb } else { 0 };
+        v
+    }
+}
+
+impl Default for ClickDetection {
+    fn default() -> Self {
+        Self {
+            source: ClickSource::Double,
+            xyz_axes_enabled: (true, true, true),
+            threshold_mg: 1500,
+            time: ClickTime::default(),
+        }
+    }
+}
+
+/// Complete device configuration, written in one go with `Lis2dh12::apply`,
+/// the default is 100 Hz high-resolution ±2g on all axes with block data update
+#[derive(Copy, Clone)]
+pub struct Config {
+    pub odr: OutputDataRate,
+    pub mode: OperatingMode,
+    pub scale: FullScaleSelection,
+    pub xyz_axes_enabled: (bool, bool, bool), // (x, y, z)
+    pub bdu: bool,                            // block data update (`BDU`)
+    pub temperature: bool,                    // temperature sensor (`TEMP_EN`)
+    pub high_pass: HighPassConfig,
+    pub fifo: Option<FifoConfig>, // `None` disables the FIFO (`FIFO_EN`)
+    pub routing: InterruptRouting,
+    pub ia1: InertialInterruptConfig,
+    pub ia2: InertialInterruptConfig,
+    pub four_d_ia1: bool, // 4D detection on interrupt generator 1 (`D4D_INT1`)
+    pub four_d_ia2: bool, // 4D detection on interrupt generator 2 (`D4D_INT2`)
+    pub click: Option<ClickDetection>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            odr: OutputDataRate::Hz100,
+            mode: OperatingMode::HighResolution,
+            scale: FullScaleSelection::PlusMinus2G,
+            xyz_axes_enabled: (true, true, true),
+            bdu: true,
+            temperature: false,
+            high_pass: HighPassConfig::default(),
+            fifo: None,
+            routing: InterruptRouting::default(),
+            ia1: InertialInterruptConfig::default(),
+            ia2: InertialInterruptConfig::default(),
+            four_d_ia1: false,
+            four_d_ia2: false,
+            click: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn odr(mut self, odr: OutputDataRate) -> Self {
+        self.odr = odr;
+        self
+    }
+
+    pub fn mode(mut self, mode: OperatingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn scale(mut self, scale: FullScaleSelection) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn axes(mut self, xyz: (bool, bool, bool)) -> Self {
+        self.xyz_axes_enabled = xyz;
+        self
+    }
+
+    pub fn bdu(mut self, bdu: bool) -> Self {
+        self.bdu = bdu;
+        self
+    }
+
+    pub fn temperature(mut self, enable: bool) -> Self {
+        self.temperature = enable;
+        self
+    }
+
+    pub fn high_pass(mut self, high_pass: HighPassConfig) -> Self {
+        self.high_pass = high_pass;
+        self
+    }
+
+    pub fn fifo(mut self, fifo: Option<FifoConfig>) -> Self {
+        self.fifo = fifo;
+        self
+    }
+
+    pub fn routing(mut self, routing: InterruptRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Inertial interrupt generator configuration with 4D detection (only used in 6D modes)
+    pub fn inertial(
+        mut self,
+        generator: InterruptGenerator,
+        config: InertialInterruptConfig,
+        four_d: bool,
+    ) -> Self {
+        match generator {
+            InterruptGenerator::Int1 => {
+                self.ia1 = config;
+                self.four_d_ia1 = four_d;
+            }
+            InterruptGenerator::Int2 => {
+                self.ia2 = config;
+                self.four_d_ia2 = four_d;
+            }
+        }
+        self
+    }
+
+    pub fn click(mut self, click: Option<ClickDetection>) -> Self {
+        self.click = click;
+        self
+    }
+}
+
+/// Combination of settings not allowed by the datasheet
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// `HighRate0` (1.620 kHz) is only available in low-power mode
+    HighRate0RequiresLowPower,
+    /// The temperature sensor requires block data update (`BDU`)
+    TemperatureRequiresBdu,
+    /// `HR` and `LPen` must not be set at the same time
+    HighResolutionAndLowPower,
+    /// Big/little endian selection (`BLE`) is only available in high-resolution mode
+    BleRequiresHighResolution,
+    /// The click threshold or timing cannot be represented at the configured scale and data rate
+    ClickOutOfRange,
+}
+
+/// Output data rate and operating mode combination
+pub(crate) fn check_mode(odr: OutputDataRate, mode: OperatingMode) -> Result<(), ConfigError> {
+    match (odr, mode) {
+        (OutputDataRate::HighRate0, OperatingMode::LowPower) => Ok(()),
+        (OutputDataRate::HighRate0, _) => Err(ConfigError::HighRate0RequiresLowPower),
+        _ => Ok(()),
+    }
+}
+
+impl Config {
+    /// Check the configuration against the datasheet constraints
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        check_mode(self.odr, self.mode)?;
+        if self.temperature && !self.bdu {
+            return Err(ConfigError::TemperatureRequiresBdu);
+        }
+        Ok(())
+    }
+}
+
+/// Register values of a `Config`
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ConfigRegisters {
+    pub temp_cfg_reg: u8,
+    pub ctrl_reg1: u8,
+    pub ctrl_reg2: u8,
+    pub ctrl_reg3: u8,
+    pub ctrl_reg4: u8,
+    pub ctrl_reg5: u8,
+    pub ctrl_reg6: u8,
+    pub fifo_ctrl_reg: u8,
+    pub int1_cfg: u8,
+    pub int1_ths: u8,
+    pub int1_duration: u8,
+    pub int2_cfg: u8,
+    pub int2_ths: u8,
+    pub int2_duration: u8,
+    pub click_cfg: u8,
+    pub click_ths: u8,
+    pub time_limit: u8,
+    pub time_latency: u8,
+    pub time_window: u8,
+}
+
+impl ConfigRegisters {
+    /// (register, value) pairs in address order
+    pub fn registers(&self) -> [(Register, u8); 19] {
+        [
+            (Register::TEMP_CFG_REG, self.temp_cfg_reg),
+            (Register::CTRL_REG1, self.ctrl_reg1),
+            (Register::CTRL_REG2, self.ctrl_reg2),
+            (Register::CTRL_REG3, self.ctrl_reg3),
+            (Register::CTRL_REG4, self.ctrl_reg4),
+            (Register::CTRL_REG5, self.ctrl_reg5),
+            (Register::CTRL_REG6, self.ctrl_reg6),
+            (Register::FIFO_CTRL_REG, self.fifo_ctrl_reg),
+            (Register::INT1_CFG, self.int1_cfg),
+            (Register::INT1_THS, self.int1_ths),
+            (Register::INT1_DURATION, self.int1_duration),
+            (Register::INT2_CFG, self.int2_cfg),
+            (Register::INT2_THS, self.int2_ths),
+            (Register::INT2_DURATION, self.int2_duration),
+            (Register::CLICK_CFG, self.click_cfg),
+            (Register::CLICK_THS, self.click_ths),
+            (Register::TIME_LIMIT, self.time_limit),
+            (Register::TIME_LATENCY, self.time_latency),
+            (Register::TIME_WINDOW, self.time_window),
+        ]
+    }
+}
+
+impl Config {
+    /// Register values of the configuration,
+    /// fails with `ClickOutOfRange` if the click settings cannot be represented at its scale and data rate
+    pub fn registers(&self) -> Result<ConfigRegisters, ConfigError> {
+        let (x, y, z) = self.xyz_axes_enabled;
+        let mut ctrl_reg1 = (self.odr as u8) << 4;
+        ctrl_reg1 |= if x { Xen } else { 0 };
+        ctrl_reg1 |= if y { Yen } else { 0 };
+        ctrl_reg1 |= if z { Zen } else { 0 };
+
+        let mut ctrl_reg4 = (self.scale as u8) << 4;
+        ctrl_reg4 |= if self.bdu { BDU } else { 0 };
+
+        match self.mode {
+            OperatingMode::LowPower => ctrl_reg1 |= LPen,
+            OperatingMode::Normal => {}
+            OperatingMode::HighResolution => ctrl_reg4 |= HR,
+        }
+
+        let routing = &self.routing;
+        let mut ctrl_reg5 = 0;
+        ctrl_reg5 |= if self.fifo.is_some() { FIFO_EN } else { 0 };
+        ctrl_reg5 |= if routing.latch_ia1 { LIR_INT1 } else { 0 };
+        ctrl_reg5 |= if self.four_d_ia1 { D4D_INT1 } else { 0 };
+        ctrl_reg5 |= if routing.latch_ia2 { LIR_INT2 } else { 0 };
+        ctrl_reg5 |= if self.four_d_ia2 { D4D_INT2 } else { 0 };
+
+        let polarity = if routing.active_low { INT_POLARITY } else { 0 };
+
+        let mut regs = ConfigRegisters {
+            temp_cfg_reg: if self.temperature { TEMP_EN } else { 0 },
+            ctrl_reg1,
+            ctrl_reg2: self.high_pass.bits(),
+            ctrl_reg3: routing.int1.bits(),
+            ctrl_reg4,
+            ctrl_reg5,
+            ctrl_reg6: routing.int2.bits() | polarity,
+            fifo_ctrl_reg: self.fifo.map_or(0, |fifo| fifo.bits()),
+            int1_cfg: self.ia1.cfg_bits(),
+            int1_ths: self.ia1.threshold & THS_MASK,
+            int1_duration: self.ia1.duration & D_MASK,
+            int2_cfg: self.ia2.cfg_bits(),
+            int2_ths: self.ia2.threshold & THS_MASK,
+            int2_duration: self.ia2.duration & D_MASK,
+            ..ConfigRegisters::default()
+        };
+
+        if let Some(click) = &self.click {
+            let ths = click_ths(self.scale, click.threshold_mg)?;
+            let lir = if routing.latch_click { LIR_Click } else { 0 };
+            regs.click_cfg = click.cfg_bits();
+            regs.click_ths = ths | lir;
+            if let Some((limit, latency, window)) =
+                click_time_cycles(self.odr, self.mode, &click.time)?
+            {
+                regs.time_limit = limit & TLI_MASK;
+                regs.time_latency = latency;
+                regs.time_window = window;
+            }
+        }
+        Ok(regs)
+    }
+}
+
+/// Version of the `Config::to_bytes` layout
+pub const CONFIG_BLOB_VERSION: u8 = 1;
+
+/// Length of a serialized `Config` in bytes
+pub const CONFIG_BLOB_LEN: usize = 26;
+
+/// Reason a serialized configuration was rejected
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigBlobError {
+    /// The blob is shorter than `CONFIG_BLOB_LEN`
+    Length,
+    /// The blob was written with an unknown layout version
+    Version(u8),
+    /// The checksum does not match, the blob is corrupt
+    Crc,
+    /// A field holds a value that does not decode to a valid setting
+    InvalidValue,
+}
+
+impl Config {
+    /// Compact serialization for persistent storage,
+    /// a version byte followed by the settings and a CRC-8 of all preceding bytes
+    pub fn to_bytes(self) -> [u8; CONFIG_BLOB_LEN] {
+        let mut buf = [0u8; CONFIG_BLOB_LEN];
+        let (x, y, z) = self.xyz_axes_enabled;
+        let routing = &self.routing;
+
+        let mut flags = 0;
+        flags |= if x { 0b0000_0001 } else { 0 };
+        flags |= if y { 0b0000_0010 } else { 0 };
+        flags |= if z { 0b0000_0100 } else { 0 };
+        flags |= if self.bdu { 0b0000_1000 } else { 0 };
+        flags |= if self.temperature { 0b0001_0000 } else { 0 };
+        flags |= if self.fifo.is_some() { 0b0010_0000 } else { 0 };
+        flags |= if self.four_d_ia1 { 0b0100_0000 } else { 0 };
+        flags |= if self.four_d_ia2 { 0b1000_0000 } else { 0 };
+
+        let mut latches = 0;
+        latches |= if routing.latch_ia1 { 0b001 } else { 0 };
+        latches |= if routing.latch_ia2 { 0b010 } else { 0 };
+        latches |= if routing.latch_click { 0b100 } else { 0 };
+
+        buf[0] = CONFIG_BLOB_VERSION;
+        buf[1] = self.odr as u8;
+        buf[2] = match self.mode {
+            OperatingMode::HighResolution => 0,
+            OperatingMode::Normal => 1,
+            OperatingMode::LowPower => 2,
+        };
+        buf[3] = self.scale as u8;
+        buf[4] = flags;
+        buf[5] = self.high_pass.bits();
+        buf[6] = self.fifo.unwrap_or_default().bits();
+        buf[7] = routing.int1.bits();
+        buf[8] = routing.int2.bits() | if routing.active_low { INT_POLARITY } else { 0 };
+        buf[9] = latches;
+        buf[10] = self.ia1.cfg_bits();
+        buf[11] = self.ia1.threshold & THS_MASK;
+        buf[12] = self.ia1.duration & D_MASK;
+        buf[13] = self.ia2.cfg_bits();
+        buf[14] = self.ia2.threshold & THS_MASK;
+        buf[15] = self.ia2.duration & D_MASK;
+
+        if let Some(click) = &self.click {
+            let (x, y, z) = click.xyz_axes_enabled;
+            let mut v = 0b1000_0000; // click detection enabled
+            v |= if click.source == ClickSource::Double {
+                0b0100_0000
+            } else {
+                0
+            };
+            v |= if x { 0b001 } else { 0 };
+            v |= if y { 0b010 } else { 0 };
+            v |= if z { 0b100 } else { 0 };
+            buf[16] = v;
+            buf[17..19].copy_from_slice(&click.threshold_mg.to_le_bytes());
+            buf[19..21].copy_from_slice(&click.time.limit_ms.to_le_bytes());
+            buf[21..23].copy_from_slice(&click.time.latency_ms.to_le_bytes());
+            buf[23..25].copy_from_slice(&click.time.window_ms.to_le_bytes());
+        }
+
+        buf[CONFIG_BLOB_LEN - 1] = crc8(&buf[..CONFIG_BLOB_LEN - 1]);
+        buf
+    }
+
+    /// Configuration serialized with `to_bytes`,
+    /// rejected if it is of an unknown version, truncated or corrupt (checked in that order,
+    /// the layout and length of other versions are unknown)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ConfigBlobError> {
+        match bytes.first() {
+            None => return Err(ConfigBlobError::Length),
+            Some(&version) if version != CONFIG_BLOB_VERSION => {
+                return Err(ConfigBlobError::Version(version))
+            }
+            Some(_) => {}
+        }
+        if bytes.len() < CONFIG_BLOB_LEN {
+            return Err(ConfigBlobError::Length);
+        }
+        let buf = &bytes[..CONFIG_BLOB_LEN];
+        if crc8(&buf[..CONFIG_BLOB_LEN - 1]) != buf[CONFIG_BLOB_LEN - 1] {
+            return Err(ConfigBlobError::Crc);
+        }
+
+        let invalid = ConfigBlobError::InvalidValue;
+        let flags = buf[4];
+        let latches = buf[9];
+        let u16_at = |i: usize| u16::from_le_bytes([buf[i], buf[i + 1]]);
+
+        let fifo = if (flags & 0b0010_0000) != 0 {
+            Some(FifoConfig::from_bits(buf[6]).ok_or(invalid)?)
+        } else {
+            None
+        };
+
+        let click = if (buf[16] & 0b1000_0000) != 0 {
+            let v = buf[16];
+            Some(ClickDetection {
+                source: if (v & 0b0100_0000) != 0 {
+                    ClickSource::Double
+                } else {
+                    ClickSource::Single
+                },
+                xyz_axes_enabled: ((v & 0b001) != 0, (v & 0b010) != 0, (v & 0b100) != 0),
+                threshold_mg: u16_at(17),
+                time: ClickTime {
+                    limit_ms: u16_at(19),
+                    latency_ms: u16_at(21),
+                    window_ms: u16_at(23),
+                },
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            odr: OutputDataRate::from_u8(buf[1]).ok_or(invalid)?,
+            mode: match buf[2] {
+                0 => OperatingMode::HighResolution,
+                1 => OperatingMode::Normal,
+                2 => OperatingMode::LowPower,
+                _ => return Err(invalid),
+            },
+            scale: FullScaleSelection::from_u8(buf[3]).ok_or(invalid)?,
+            xyz_axes_enabled: (
+                (flags & 0b0000_0001) != 0,
+                (flags & 0b0000_0010) != 0,
+                (flags & 0b0000_0100) != 0,
+            ),
+            bdu: (flags & 0b0000_1000) != 0,
+            temperature: (flags & 0b0001_0000) != 0,
+            high_pass: HighPassConfig::from_bits(buf[5]).ok_or(invalid)?,
+            fifo,
+            routing: InterruptRouting {
+                int1: Int1Routing::from_bits(buf[7]),
+                int2: Int2Routing::from_bits(buf[8]),
+                active_low: (buf[8] & INT_POLARITY) != 0,
+                latch_ia1: (latches & 0b001) != 0,
+                latch_ia2: (latches & 0b010) != 0,
+                latch_click: (latches & 0b100) != 0,
+            },
+            ia1: InertialInterruptConfig::from_bits(buf[10], buf[11], buf[12]).ok_or(invalid)?,
+            ia2: InertialInterruptConfig::from_bits(buf[13], buf[14], buf[15]).ok_or(invalid)?,
+            four_d_ia1: (flags & 0b0100_0000) != 0,
+            four_d_ia2: (flags & 0b1000_0000) != 0,
+            click,
+        })
+    }
+}
+
+// CRC-8 with polynomial x^8 + x^2 + x + 1 (0x07), initial value 0
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if (crc & 0x80) != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut routing = InterruptRouting::default();
+        routing.int1.click = true;
+        routing.int2.ia1 = true;
+        routing.active_low = true;
+        routing.latch_ia1 = true;
+
+        Config::default()
+            .odr(OutputDataRate::Hz400)
+            .mode(OperatingMode::Normal)
+            .scale(FullScaleSelection::PlusMinus8G)
+            .axes((true, false, true))
+            .temperature(true)
+            .fifo(Some(FifoConfig {
+                mode: FifoMode::StreamToFifo,
+                trigger: InterruptGenerator::Int2,
+                watermark: 10,
+            }))
+            .routing(routing)
+            .inertial(
+                InterruptGenerator::Int1,
+                InertialInterruptConfig {
+                    mode: Aoi6d::Position6D,
+                    xyz_low_enabled: (true, false, false),
+                    xyz_high_enabled: (false, true, true),
+                    threshold: 20,
+                    duration: 3,
+                },
+                true,
+            )
+            .click(Some(ClickDetection {
+                source: ClickSource::Single,
+                xyz_axes_enabled: (false, false, true),
+                threshold_mg: 1200,
+                time: ClickTime {
+                    limit_ms: 30,
+                    latency_ms: 120,
+                    window_ms: 300,
+                },
+            }))
+    }
+
+    // blob with the checksum recomputed after a change
+    fn with_crc(mut bytes: [u8; CONFIG_BLOB_LEN]) -> [u8; CONFIG_BLOB_LEN] {
+        bytes[CONFIG_BLOB_LEN - 1] = crc8(&bytes[..CONFIG_BLOB_LEN - 1]);
+        bytes
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = config();
+        let bytes = config.to_bytes();
+        let decoded = Config::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(decoded.registers(), config.registers());
+        assert_eq!(decoded.click.unwrap().threshold_mg, 1200);
+        assert_eq!(decoded.click.unwrap().time.window_ms, 300);
+    }
+
+    #[test]
+    fn round_trip_default() {
+        let bytes = Config::default().to_bytes();
+        let decoded = Config::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert!(decoded.fifo.is_none());
+        assert!(decoded.click.is_none());
+    }
+
+    #[test]
+    fn trailing_bytes_are_ignored() {
+        let mut buf = [0xff; CONFIG_BLOB_LEN + 4];
+        buf[..CONFIG_BLOB_LEN].copy_from_slice(&config().to_bytes());
+        assert!(Config::from_bytes(&buf).is_ok());
+    }
+
+    #[test]
+    fn length() {
+        let bytes = config().to_bytes();
+        assert_eq!(Config::from_bytes(&[]).err(), Some(ConfigBlobError::Length));
+        assert_eq!(
+            Config::from_bytes(&bytes[..CONFIG_BLOB_LEN - 1]).err(),
+            Some(ConfigBlobError::Length)
+        );
+    }
+
+    #[test]
+    fn version() {
+        let mut bytes = config().to_bytes();
+        bytes[0] = CONFIG_BLOB_VERSION + 1;
+        assert_eq!(
+            Config::from_bytes(&with_crc(bytes)).err(),
+            Some(ConfigBlobError::Version(CONFIG_BLOB_VERSION + 1))
+        );
+        // the version is checked before the length
+        assert_eq!(
+            Config::from_bytes(&[CONFIG_BLOB_VERSION + 1]).err(),
+            Some(ConfigBlobError::Version(CONFIG_BLOB_VERSION + 1))
+        );
+    }
+
+    #[test]
+    fn crc() {
+        let mut bytes = config().to_bytes();
+        bytes[3] ^= 0x01;
+        assert_eq!(Config::from_bytes(&bytes).err(), Some(ConfigBlobError::Crc));
+    }
+
+    #[test]
+    fn invalid_value() {
+        let mut bytes = config().to_bytes();
+        bytes[2] = 3; // operating mode
+        assert_eq!(
+            Config::from_bytes(&with_crc(bytes)).err(),
+            Some(ConfigBlobError::InvalidValue)
+        );
+    }
+}
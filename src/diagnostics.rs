@@ -0,0 +1,120 @@
+use crate::*;
+
+/// Snapshot of all mapped registers,
+/// see `Lis2dh12::dump_registers`,
+/// printed as register name and value pairs
+#[derive(Copy, Clone)]
+pub struct RegisterDump {
+    values: [u8; REGISTERS.len()], // in the order of `REGISTERS`
+}
+
+/// Register whose value differs from the expected configuration
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegisterDiff {
+    pub register: Register,
+    pub expected: u8,
+    pub actual: u8,
+}
+
+impl RegisterDump {
+    pub(crate) fn new(values: [u8; REGISTERS.len()]) -> Self {
+        Self { values }
+    }
+
+    /// Value of a register
+    pub fn get(&self, register: Register) -> u8 {
+        let index = REGISTERS
+            .iter()
+            .position(|r| *r == register)
+            .unwrap_or_default();
+        self.values[index]
+    }
+
+    /// (register, value) pairs in address order
+    pub fn iter(&self) -> impl Iterator<Item = (Register, u8)> + '_ {
+        REGISTERS.iter().copied().zip(self.values.iter().copied())
+    }
+
+    /// Raw values in address order, one byte per entry of `REGISTERS`
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.values
+    }
+
+    /// Configuration registers of the dump,
+    /// decode with `Lis2dh12::config_from_registers`
+    pub fn config_registers(&self) -> ConfigRegisters {
+        ConfigRegisters {
+            temp_cfg_reg: self.get(Register::TEMP_CFG_REG),
+            ctrl_reg1: self.get(Register::CTRL_REG1),
+            ctrl_reg2: self.get(Register::CTRL_REG2),
+            ctrl_reg3: self.get(Register::CTRL_REG3),
+            ctrl_reg4: self.get(Register::CTRL_REG4),
+            ctrl_reg5: self.get(Register::CTRL_REG5),
+            ctrl_reg6: self.get(Register::CTRL_REG6),
+            fifo_ctrl_reg: self.get(Register::FIFO_CTRL_REG),
+            int1_cfg: self.get(Register::INT1_CFG),
+            int1_ths: self.get(Register::INT1_THS),
+            int1_duration: self.get(Register::INT1_DURATION),
+            int2_cfg: self.get(Register::INT2_CFG),
+            int2_ths: self.get(Register::INT2_THS),
+            int2_duration: self.get(Register::INT2_DURATION),
+            click_cfg: self.get(Register::CLICK_CFG),
+            click_ths: self.get(Register::CLICK_THS),
+            time_limit: self.get(Register::TIME_LIMIT),
+            time_latency: self.get(Register::TIME_LATENCY),
+            time_window: self.get(Register::TIME_WINDOW),
+        }
+    }
+
+    /// Configuration registers that differ from the expected values,
+    /// see `Config::registers`
+    pub fn diff(&self, expected: &ConfigRegisters) -> impl Iterator<Item = RegisterDiff> + '_ {
+        let expected = expected.registers();
+        (0..expected.len()).filter_map(move |i| {
+            let (register, expected) = expected[i];
+            let actual = self.get(register);
+            if actual != expected {
+                Some(RegisterDiff {
+                    register,
+                    expected,
+                    actual,
+                })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Configuration registers that differ from an expected configuration,
+    /// fails if the configuration cannot be converted to register values
+    pub fn diff_config(
+        &self,
+        expected: &Config,
+    ) -> Result<impl Iterator<Item = RegisterDiff> + '_, ConfigError> {
+        let expected = expected.registers()?;
+        Ok(self.diff(&expected))
+    }
+}
+
+impl core::fmt::Debug for RegisterDump {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut dump = f.debug_struct("RegisterDump");
+        for (register, value) in self.iter() {
+            dump.field(register.name(), &value);
+        }
+        dump.finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RegisterDump {
+    fn format(&self, f: defmt::Formatter) {
+        // a formatter takes a single write, so the pairs are collected first
+        let mut named = [("", 0u8); REGISTERS.len()];
+        for (entry, (register, value)) in named.iter_mut().zip(self.iter()) {
+            *entry = (register.name(), value);
+        }
+        defmt::write!(f, "RegisterDump {}", &named[..]);
+    }
+}
@@ -0,0 +1,435 @@
+use crate::{ClickEvent, ClickSign, ClickSource};
+
+/// Maximum number of taps in a pattern
+pub const MAX_TAPS: usize = 8;
+
+/// Face that was tapped,
+/// the axis and sign of the acceleration reported in CLICK_SRC
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Face {
+    XPositive,
+    XNegative,
+    YPositive,
+    YNegative,
+    ZPositive,
+    ZNegative,
+}
+
+impl Face {
+    /// Face of a click event, `None` if no axis was reported
+    pub fn from_click(event: &ClickEvent) -> Option<Self> {
+        let negative = event.sign == ClickSign::Negative;
+        match (event.xyz, negative) {
+            ((true, _, _), false) => Some(Face::XPositive),
+            ((true, _, _), true) => Some(Face::XNegative),
+            ((_, true, _), false) => Some(Face::YPositive),
+            ((_, true, _), true) => Some(Face::YNegative),
+            ((_, _, true), false) => Some(Face::ZPositive),
+            ((_, _, true), true) => Some(Face::ZNegative),
+            _ => None,
+        }
+    }
+}
+
+/// One tap of a pattern
+#[derive(Copy, Clone)]
+pub struct TapStep {
+    pub face: Option<Face>, // face that must be tapped, `None` for any face
+    pub gap_ms: u16,        // expected time since the previous tap (ignored for the first tap)
+}
+
+/// Sequence of taps recognised as a gesture
+#[derive(Copy, Clone)]
+pub struct TapPattern<'a> {
+    pub steps: &'a [TapStep],
+    pub tolerance_ms: u16, // allowed deviation from each expected gap
+}
+
+/// Three taps on any face, about 250ms apart
+pub const TRIPLE_TAP: TapPattern<'static> = TapPattern {
+    steps: &[
+        TapStep {
+            face: None,
+            gap_ms: 0,
+        },
+        TapStep {
+            face: None,
+            gap_ms: 250,
+        },
+        TapStep {
+            face: None,
+            gap_ms: 250,
+        },
+    ],
+    tolerance_ms: 150,
+};
+
+#[derive(Copy, Clone)]
+struct Tap {
+    face: Option<Face>,
+    time_ms: u32,
+    // second tap of a hardware double click, its gap was already checked by the device
+    paired: bool,
+}
+
+/// Tap gesture recognizer,
+/// fed with the click events read from CLICK_SRC (single-click mode, or double-click mode
+/// where a double click counts as two taps) and a millisecond timestamp
+///
+/// A gesture is the whole sequence of taps up to a pause longer than any pattern allows.
+/// When a completed pattern is also the start of a longer one, it is only reported by
+/// `poll` once that pause has passed.
+pub struct TapRecognizer<'a> {
+    patterns: &'a [TapPattern<'a>],
+    taps: [Tap; MAX_TAPS],
+    len: usize,
+    ready: Option<usize>,
+}
+
+impl<'a> TapRecognizer<'a> {
+    pub fn new(patterns: &'a [TapPattern<'a>]) -> Self {
+        Self {
+            patterns,
+            taps: [Tap {
+                face: None,
+                time_ms: 0,
+                paired: false,
+            }; MAX_TAPS],
+            len: 0,
+            ready: None,
+        }
+    }
+
+    /// Forget the taps seen so far
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.ready = None;
+    }
+
+    /// Record a click event, returns the index of the pattern it completes
+    pub fn on_click(&mut self, event: &ClickEvent, now_ms: u32) -> Option<usize> {
+        // finish the previous sequence if it has timed out
+        let finished = self.poll(now_ms);
+
+        let taps = match event.source {
+            Some(ClickSource::Single) if event.active => 1,
+            Some(ClickSource::Double) if event.active => 2,
+            _ => return finished,
+        };
+
+        let face = Face::from_click(event);
+        for i in 0..taps {
+            self.push(Tap {
+                face,
+                time_ms: now_ms,
+                paired: i > 0,
+            });
+        }
+
+        let recognized = self.evaluate(taps);
+        match finished {
+            Some(_) => {
+                self.ready = recognized;
+                finished
+            }
+            None => recognized,
+        }
+    }
+
+    /// Report a pattern that was waiting for a possible longer one once the sequence has timed out
+    pub fn poll(&mut self, now_ms: u32) -> Option<usize> {
+        if let Some(index) = self.ready.take() {
+            return Some(index);
+        }
+
+        if self.len == 0 {
+            return None;
+        }
+
+        let last = self.taps[self.len - 1].time_ms;
+        if now_ms.wrapping_sub(last) <= self.timeout_ms() {
+            return None;
+        }
+
+        let recognized = self.complete_match(self.len);
+        self.len = 0;
+        recognized
+    }
+
+    fn push(&mut self, tap: Tap) {
+        if self.len == MAX_TAPS {
+            self.taps.copy_within(1.., 0);
+            self.len -= 1;
+        }
+        self.taps[self.len] = tap;
+        self.len += 1;
+    }
+
+    // reports a completed pattern unless a longer pattern could still match,
+    // `added` is the number of taps just pushed
+    fn evaluate(&mut self, added: usize) -> Option<usize> {
+        if self.extendable(self.len) {
+            return None;
+        }
+
+        if let Some(index) = self.complete_match(self.len) {
+            self.len = 0;
+            return Some(index);
+        }
+
+        // the new taps broke the sequence, a pattern completed before them was held back
+        // for a longer one and is reported now
+        let held = self.complete_match(self.len - added);
+
+        // restart from the breaking tap, dropping taps that cannot start a pattern
+        self.taps.copy_within(self.len - added..self.len, 0);
+        self.len = added;
+        let mut recognized = None;
+        while self.len > 0 {
+            if self.extendable(self.len) {
+                break;
+            }
+            if let Some(index) = self.complete_match(self.len) {
+                self.len = 0;
+                recognized = Some(index);
+                break;
+            }
+            self.taps.copy_within(1..self.len, 0);
+            self.len -= 1;
+        }
+
+        match held {
+            Some(_) => {
+                self.ready = recognized;
+                held
+            }
+            None => recognized,
+        }
+    }
+
+    // whether a longer pattern could still match the first `len` taps
+    fn extendable(&self, len: usize) -> bool {
+        let taps = &self.taps[..len];
+        self.patterns
+            .iter()
+            .any(|p| p.steps.len() > taps.len() && Self::matches(p, taps))
+    }
+
+    // pattern matching exactly the first `len` taps
+    fn complete_match(&self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return None;
+        }
+        let taps = &self.taps[..len];
+        self.patterns
+            .iter()
+            .position(|p| p.steps.len() == taps.len() && Self::matches(p, taps))
+    }
+
+    // whether the taps match the start of the pattern
+    fn matches(pattern: &TapPattern, taps: &[Tap]) -> bool {
+        if taps.len() > pattern.steps.len() {
+            return false;
+        }
+
+        taps.iter()
+            .zip(pattern.steps.iter())
+            .enumerate()
+            .all(|(i, (tap, step))| {
+                let face_ok = step.face.is_none() || step.face == tap.face;
+                let gap_ok = i == 0 || tap.paired || {
+                    let gap = tap.time_ms.wrapping_sub(taps[i - 1].time_ms) as i64;
+                    let deviation = (gap - step.gap_ms as i64).unsigned_abs();
+                    deviation <= pattern.tolerance_ms as u64
+                };
+                face_ok && gap_ok
+            })
+    }
+
+    // longest pause allowed between two taps of any pattern
+    fn timeout_ms(&self) -> u32 {
+        self.patterns
+            .iter()
+            .flat_map(|p| {
+                p.steps
+                    .iter()
+                    .map(move |s| s.gap_ms as u32 + p.tolerance_ms as u32)
+            })
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const Z_DOUBLE_TAP: TapPattern<'static> = TapPattern {
+        steps: &[
+            TapStep {
+                face: Some(Face::ZPositive),
+                gap_ms: 0,
+            },
+            TapStep {
+                face: Some(Face::ZPositive),
+                gap_ms: 300,
+            },
+        ],
+        tolerance_ms: 100,
+    };
+
+    fn click(source: ClickSource, xyz: (bool, bool, bool), sign: ClickSign) -> ClickEvent {
+        ClickEvent {
+            active: true,
+            source: Some(source),
+            xyz,
+            sign,
+        }
+    }
+
+    fn tap() -> ClickEvent {
+        click(
+            ClickSource::Single,
+            (false, false, true),
+            ClickSign::Positive,
+        )
+    }
+
+    #[test]
+    fn face_from_click() {
+        let face = |xyz, sign| Face::from_click(&click(ClickSource::Single, xyz, sign));
+        assert_eq!(
+            face((true, false, false), ClickSign::Positive),
+            Some(Face::XPositive)
+        );
+        assert_eq!(
+            face((false, true, false), ClickSign::Negative),
+            Some(Face::YNegative)
+        );
+        assert_eq!(
+            face((false, false, true), ClickSign::Negative),
+            Some(Face::ZNegative)
+        );
+        // the first reported axis wins
+        assert_eq!(
+            face((false, true, true), ClickSign::Positive),
+            Some(Face::YPositive)
+        );
+        assert_eq!(face((false, false, false), ClickSign::Positive), None);
+    }
+
+    #[test]
+    fn triple_tap() {
+        let patterns = [TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 1000), None);
+        assert_eq!(r.on_click(&tap(), 1250), None);
+        assert_eq!(r.on_click(&tap(), 1500), Some(0));
+        assert_eq!(r.poll(5000), None);
+    }
+
+    #[test]
+    fn triple_tap_tolerance() {
+        let patterns = [TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&tap(), 400), None); // 250 + 150
+        assert_eq!(r.on_click(&tap(), 500), Some(0)); // 250 - 150
+    }
+
+    #[test]
+    fn triple_tap_too_slow() {
+        let patterns = [TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&tap(), 250), None);
+        // the sequence has timed out, the tap starts a new one
+        assert_eq!(r.on_click(&tap(), 651), None);
+        assert_eq!(r.on_click(&tap(), 900), None);
+        assert_eq!(r.on_click(&tap(), 1150), Some(0));
+    }
+
+    #[test]
+    fn restart_from_breaking_tap() {
+        let patterns = [TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&tap(), 250), None);
+        // too early for the third tap, but a valid first tap
+        assert_eq!(r.on_click(&tap(), 300), None);
+        assert_eq!(r.on_click(&tap(), 550), None);
+        assert_eq!(r.on_click(&tap(), 800), Some(0));
+    }
+
+    #[test]
+    fn double_click_counts_as_two_taps() {
+        let patterns = [TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        let double = click(
+            ClickSource::Double,
+            (false, false, true),
+            ClickSign::Positive,
+        );
+        assert_eq!(r.on_click(&double, 0), None);
+        assert_eq!(r.on_click(&tap(), 250), Some(0));
+    }
+
+    #[test]
+    fn wrong_face() {
+        let patterns = [Z_DOUBLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        let x_tap = click(
+            ClickSource::Single,
+            (true, false, false),
+            ClickSign::Positive,
+        );
+        let z_negative = click(
+            ClickSource::Single,
+            (false, false, true),
+            ClickSign::Negative,
+        );
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&x_tap, 300), None);
+        assert_eq!(r.on_click(&tap(), 600), None);
+        assert_eq!(r.on_click(&z_negative, 900), None);
+        assert_eq!(r.on_click(&tap(), 1200), None);
+        assert_eq!(r.on_click(&tap(), 1500), Some(0));
+    }
+
+    #[test]
+    fn inactive_click_is_ignored() {
+        let patterns = [Z_DOUBLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        let inactive = ClickEvent {
+            active: false,
+            ..tap()
+        };
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&inactive, 150), None);
+        assert_eq!(r.on_click(&tap(), 300), Some(0));
+    }
+
+    #[test]
+    fn shorter_pattern_waits_for_timeout() {
+        let patterns = [Z_DOUBLE_TAP, TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&tap(), 300), None); // could still become a triple tap
+        assert_eq!(r.poll(500), None);
+        assert_eq!(r.poll(701), Some(0));
+        assert_eq!(r.poll(2000), None);
+    }
+
+    #[test]
+    fn shorter_pattern_reported_when_longer_breaks() {
+        let patterns = [Z_DOUBLE_TAP, TRIPLE_TAP];
+        let mut r = TapRecognizer::new(&patterns);
+        assert_eq!(r.on_click(&tap(), 0), None);
+        assert_eq!(r.on_click(&tap(), 300), None);
+        // too early for a triple tap, the double tap is reported and the tap starts over
+        assert_eq!(r.on_click(&tap(), 320), Some(0));
+        assert_eq!(r.on_click(&tap(), 570), None);
+        assert_eq!(r.on_click(&tap(), 820), Some(1));
+        assert_eq!(r.poll(5000), None);
+    }
+}
@@ -1,4 +1,5 @@
 #![no_std]
+#![feature(generic_associated_types)]
 
 mod reg;
 pub use crate::reg::*;
@@ -6,6 +7,15 @@ pub use crate::reg::*;
 mod non_blocking;
 pub use non_blocking::*;
 
+mod gesture;
+pub use gesture::*;
+
+mod config;
+pub use config::*;
+
+mod diagnostics;
+pub use diagnostics::*;
+
 use core::fmt::Debug;
 
 pub use accelerometer::{vector::F32x3, Accelerometer};
@@ -16,7 +26,20 @@ pub enum Error<SpiError, PinError> {
     Spi(SpiError),
     /// CS output pin error
     Pin(PinError),
+    /// INT1/INT2 input pin error
+    IntPin,
     InvalidWhoAmI(u8),
+    /// A value cannot be represented in its register at the current scale or data rate
+    OutOfRange,
+    /// The interrupt routing request conflicts with the polarity or latching of the current routing
+    /// or the signal is not available on (or not routed to) that pin
+    RoutingConflict,
+    /// The device did not respond in time (data ready, boot)
+    Timeout,
+    /// A register holds a value that does not decode to a valid setting
+    UnexpectedRegisterValue(Register, u8),
+    /// The requested combination of settings is not allowed by the datasheet
+    InvalidConfig(ConfigError),
 }
 
 impl<SpiError, PinError> From<SpiError> for Error<SpiError, PinError> {
@@ -25,10 +48,20 @@ impl<SpiError, PinError> From<SpiError> for Error<SpiError, PinError> {
     }
 }
 
-pub struct Lis2dh12<SPI, CS> {
+/// Interrupt pin that is not connected to the driver
+pub struct NoPin;
+
+pub struct Lis2dh12<SPI, CS, INT1 = NoPin, INT2 = NoPin> {
     spi: SPI,
     cs: CS,
+    int1: INT1,
+    int2: INT2,
     scale: FullScaleSelection,
+    odr: OutputDataRate,
+    mode: OperatingMode,
+    routing: InterruptRouting,
+    click_threshold_mg: Option<u16>,
+    click_time: Option<ClickTime>,
 }
 
 impl<SPI, CS> Lis2dh12<SPI, CS> {
@@ -36,7 +69,48 @@ impl<SPI, CS> Lis2dh12<SPI, CS> {
         Self {
             spi,
             cs,
+            int1: NoPin,
+            int2: NoPin,
             scale: FullScaleSelection::PlusMinus2G,
+            odr: OutputDataRate::PowerDown,
+            mode: OperatingMode::Normal,
+            routing: InterruptRouting::default(),
+            click_threshold_mg: None,
+            click_time: None,
+        }
+    }
+}
+
+impl<SPI, CS, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2> {
+    /// Hand the INT1 interrupt pin to the driver so that it can wait for events
+    pub fn with_int1<P>(self, int1: P) -> Lis2dh12<SPI, CS, P, INT2> {
+        Lis2dh12 {
+            spi: self.spi,
+            cs: self.cs,
+            int1,
+            int2: self.int2,
+            scale: self.scale,
+            odr: self.odr,
+            mode: self.mode,
+            routing: self.routing,
+            click_threshold_mg: self.click_threshold_mg,
+            click_time: self.click_time,
+        }
+    }
+
+    /// Hand the INT2 interrupt pin to the driver so that it can wait for events
+    pub fn with_int2<P>(self, int2: P) -> Lis2dh12<SPI, CS, INT1, P> {
+        Lis2dh12 {
+            spi: self.spi,
+            cs: self.cs,
+            int1: self.int1,
+            int2,
+            scale: self.scale,
+            odr: self.odr,
+            mode: self.mode,
+            routing: self.routing,
+            click_threshold_mg: self.click_threshold_mg,
+            click_time: self.click_time,
         }
     }
 
@@ -44,4 +118,9 @@ impl<SPI, CS> Lis2dh12<SPI, CS> {
     pub fn destroy(self) -> (SPI, CS) {
         (self.spi, self.cs)
     }
+
+    // destroy the instance and return the spi bus, its cs pin and the interrupt pins
+    pub fn destroy_with_int_pins(self) -> (SPI, CS, INT1, INT2) {
+        (self.spi, self.cs, self.int1, self.int2)
+    }
 }
@@ -1,15 +1,66 @@
 use crate::*;
 use accelerometer::vector::I16x3;
+use core::future::{pending, Future, Pending};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use embassy_traits::delay::Delay;
+use embassy_traits::gpio::{WaitForFallingEdge, WaitForHigh, WaitForLow, WaitForRisingEdge};
 use embassy_traits::spi::FullDuplex;
-use embedded_hal::digital::v2::OutputPin;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use num_traits::FromPrimitive;
 
 pub use accelerometer::{vector::F32x3, Accelerometer};
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ClickSource {
     Single,
     Double,
 }
 
+/// Sign of the acceleration that triggered a click
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClickSign {
+    Positive,
+    Negative,
+}
+
+/// Click event,
+/// decoded from CLICK_SRC register
+#[derive(Debug, Copy, Clone)]
+pub struct ClickEvent {
+    /// IA bit, one or more clicks have been detected
+    pub active: bool,
+    /// (`SClick`, `DClick`) bits, `None` if no click was detected
+    pub source: Option<ClickSource>,
+    /// (X, Y, Z) bits, axes on which the click was detected
+    pub xyz: (bool, bool, bool),
+    /// Sign bit
+    pub sign: ClickSign,
+}
+
+impl From<u8> for ClickEvent {
+    fn from(reg: u8) -> Self {
+        let source = if (reg & DClick) != 0 {
+            Some(ClickSource::Double)
+        } else if (reg & SClick) != 0 {
+            Some(ClickSource::Single)
+        } else {
+            None
+        };
+
+        Self {
+            active: (reg & IA) != 0,
+            source,
+            xyz: ((reg & X) != 0, (reg & Y) != 0, (reg & Z) != 0),
+            sign: if (reg & Sign) != 0 {
+                ClickSign::Negative
+            } else {
+                ClickSign::Positive
+            },
+        }
+    }
+}
+
 pub enum ClickInterupt {
     None,
     Int1(InterruptConfig),
@@ -22,61 +73,35 @@ impl Default for ClickInterupt {
     }
 }
 
-// See INT_DUR register on lis2dw12 spec
-pub struct ClickTimeInOdrCycles {
-    // the time it takes to for the thing to stop moving below its threshold (20ms) (e.g 20ms => 0.02 * 200hz = 4)
-    pub limit: u8,
-
-    // the minimum time between taps - a typical double click is 100ms-200ms long (e.g 100ms => 0.1 * 200hz = 20)
-    pub latency: u8,
+/// Click timing in milliseconds,
+/// converted to ODR cycles for `TIME_LIMIT`, `TIME_LATENCY` and `TIME_WINDOW`
+/// whenever the output data rate or operating mode changes
+#[derive(Copy, Clone)]
+pub struct ClickTime {
+    // the time it takes to for the thing to stop moving below its threshold (e.g 20ms)
+    pub limit_ms: u16,
 
-    // cant repeat a double click until at least 500ms has passed (also the maximum time between taps) (e.g 500ms => 0.5 * 200hz = 100)
-    pub window: u8,
-}
+    // the minimum time between taps - a typical double click is 100ms-200ms long (e.g 100ms)
+    pub latency_ms: u16,
 
-fn to_u8_non_zero(val: f64) -> u8 {
-    let x = val as u8;
-    if x == 0 {
-        1
-    } else {
-        x
-    }
+    // the maximum time between taps after the latency has passed (e.g 400ms)
+    pub window_ms: u16,
 }
 
-impl ClickTimeInOdrCycles {
-    pub fn new(odr: OutputDataRate) -> Self {
-        let factor = match odr {
-            OutputDataRate::PowerDown => 0.0,
-            OutputDataRate::Hz1 => 1.0,
-            OutputDataRate::Hz10 => 10.0,
-            OutputDataRate::Hz25 => 25.0,
-            OutputDataRate::Hz50 => 50.0,
-            OutputDataRate::Hz100 => 100.0,
-            OutputDataRate::Hz200 => 200.0,
-            OutputDataRate::Hz400 => 400.0,
-            OutputDataRate::HighRate0 => 1620.0,
-            OutputDataRate::HighRate1 => 1344.0,
-        };
-
-        // the time it takes to for the thing to stop moving below its threshold (20ms)
-        let limit = to_u8_non_zero(0.02 * factor);
-
-        // the minimum time between taps - a typical double click is 100ms-200ms long (100ms)
-        let latency = to_u8_non_zero(0.1 * factor);
-
-        // cant repeat a double click until at least 500ms has passed (also the maximum time between taps)
-        let window = to_u8_non_zero(0.5 * factor) - latency;
-
+impl Default for ClickTime {
+    fn default() -> Self {
         Self {
-            latency,
-            limit,
-            window,
+            limit_ms: 20,
+            latency_ms: 100,
+            window_ms: 400,
         }
     }
 }
 
 pub struct InterruptConfig {
     pub active_low: bool,
+    // keep the click interrupt active until `CLICK_SRC` is read (`LIR_Click`),
+    // otherwise it is active for the latency time
     pub latch: bool,
 }
 
@@ -93,8 +118,8 @@ pub struct ClickConfig {
     pub source: ClickSource,
     pub interrupt: ClickInterupt,
     pub xyz_axes_enabled: (bool, bool, bool), // (x, y, z)
-    pub threshold: u8,                        // 0-127 force applied to tap (depends on the scale)
-    pub time: ClickTimeInOdrCycles,
+    pub threshold_mg: u16,                    // force applied to tap (< full scale)
+    pub time: ClickTime,
 }
 
 impl Default for ClickConfig {
@@ -103,13 +128,549 @@ impl Default for ClickConfig {
             source: ClickSource::Double,
             interrupt: Default::default(),
             xyz_axes_enabled: (true, true, true),
-            threshold: 128, //40, //0x7F,
-            time: ClickTimeInOdrCycles::new(OutputDataRate::Hz400),
+            threshold_mg: 1500,
+            time: ClickTime::default(),
+        }
+    }
+}
+
+/// Inertial interrupt generator configuration,
+/// see `INTx_CFG`, `INTx_THS` and `INTx_DURATION`
+#[derive(Copy, Clone)]
+pub struct InertialInterruptConfig {
+    pub mode: Aoi6d,
+    pub xyz_low_enabled: (bool, bool, bool), // (x, y, z) event when below the threshold
+    pub xyz_high_enabled: (bool, bool, bool), // (x, y, z) event when above the threshold
+    pub threshold: u8, // 0-127 (16mg @ 2g, 32mg @ 4g, 62mg @ 8g, 186mg @ 16g per LSB)
+    pub duration: u8,  // 0-127 minimum event duration in ODR cycles
+}
+
+impl InertialInterruptConfig {
+    /// `INTx_CFG` register value
+    pub(crate) fn cfg_bits(&self) -> u8 {
+        let (xl, yl, zl) = self.xyz_low_enabled;
+        let (xh, yh, zh) = self.xyz_high_enabled;
+        let mut v = (self.mode as u8) << 6;
+        v |= if xl { XLIE } else { 0 };
+        v |= if xh { XHIE } else { 0 };
+        v |= if yl { YLIE } else { 0 };
+        v |= if yh { YHIE } else { 0 };
+        v |= if zl { ZLIE } else { 0 };
+        v |= if zh { ZHIE } else { 0 };
+        v
+    }
+
+    /// Decoded from `INTx_CFG`, `INTx_THS` and `INTx_DURATION`
+    pub(crate) fn from_bits(cfg: u8, ths: u8, duration: u8) -> Option<Self> {
+        Some(Self {
+            mode: Aoi6d::from_u8(cfg >> 6)?,
+            xyz_low_enabled: ((cfg & XLIE) != 0, (cfg & YLIE) != 0, (cfg & ZLIE) != 0),
+            xyz_high_enabled: ((cfg & XHIE) != 0, (cfg & YHIE) != 0, (cfg & ZHIE) != 0),
+            threshold: ths & THS_MASK,
+            duration: duration & D_MASK,
+        })
+    }
+}
+
+impl Default for InertialInterruptConfig {
+    fn default() -> Self {
+        Self {
+            mode: Aoi6d::Or,
+            xyz_low_enabled: (false, false, false),
+            xyz_high_enabled: (false, false, false),
+            threshold: 0,
+            duration: 0,
+        }
+    }
+}
+
+/// High-pass filter configuration,
+/// see `CTRL_REG2`
+#[derive(Copy, Clone)]
+pub struct HighPassConfig {
+    pub mode: HighPassMode,
+    pub cutoff: HighPassCutoff,
+    pub output: bool, // filtered data to the output registers and FIFO (`FDS`)
+    pub click: bool,  // filtered data to the click function (`HPCLICK`)
+    pub ia1: bool,    // filtered data to interrupt generator 1 (`HP_IA1`)
+    pub ia2: bool,    // filtered data to interrupt generator 2 (`HP_IA2`)
+}
+
+impl HighPassConfig {
+    /// `CTRL_REG2` register value
+    pub(crate) fn bits(&self) -> u8 {
+        let mut v = ((self.mode as u8) << 6) | ((self.cutoff as u8) << 4);
+        v |= if self.output { FDS } else { 0 };
+        v |= if self.click { HPCLICK } else { 0 };
+        v |= if self.ia2 { HP_IA2 } else { 0 };
+        v |= if self.ia1 { HP_IA1 } else { 0 };
+        v
+    }
+
+    /// Decoded from the `CTRL_REG2` register value
+    pub(crate) fn from_bits(v: u8) -> Option<Self> {
+        Some(Self {
+            mode: HighPassMode::from_u8(v >> 6)?,
+            cutoff: HighPassCutoff::from_u8((v & HPCF_MASK) >> 4)?,
+            output: (v & FDS) != 0,
+            click: (v & HPCLICK) != 0,
+            ia1: (v & HP_IA1) != 0,
+            ia2: (v & HP_IA2) != 0,
+        })
+    }
+}
+
+impl Default for HighPassConfig {
+    fn default() -> Self {
+        Self {
+            mode: HighPassMode::NormalWithReset,
+            cutoff: HighPassCutoff::OdrDiv50,
+            output: false,
+            click: false,
+            ia1: false,
+            ia2: false,
+        }
+    }
+}
+
+/// Self-test result,
+/// output change between self-test enabled and disabled in 10-bit LSB (normal mode, ±2g)
+#[derive(Debug, Copy, Clone)]
+pub struct SelfTestReport {
+    pub delta: (i16, i16, i16),   // (x, y, z)
+    pub pass: (bool, bool, bool), // (x, y, z) within `SELF_TEST_MIN`..=`SELF_TEST_MAX`
+}
+
+impl SelfTestReport {
+    /// All axes are within the datasheet limits
+    pub fn passed(&self) -> bool {
+        let (x, y, z) = self.pass;
+        x && y && z
+    }
+}
+
+/// Signals routed to the `INT1` pin,
+/// see `CTRL_REG3`
+#[derive(Copy, Clone, Default)]
+pub struct Int1Routing {
+    pub click: bool,      // click interrupt (`I1_CLICK`)
+    pub ia1: bool,        // interrupt generator 1 (`I1_IA1`)
+    pub ia2: bool,        // interrupt generator 2 (`I1_IA2`)
+    pub data_ready: bool, // new XYZ data available (`I1_ZYXDA`)
+    pub watermark: bool,  // FIFO watermark (`I1_WTM`)
+    pub overrun: bool,    // FIFO overrun (`I1_OVERRUN`)
+}
+
+impl Int1Routing {
+    /// `CTRL_REG3` routing bits
+    pub(crate) fn bits(&self) -> u8 {
+        let mut v = 0;
+        v |= if self.click { I1_CLICK } else { 0 };
+        v |= if self.ia1 { I1_IA1 } else { 0 };
+        v |= if self.ia2 { I1_IA2 } else { 0 };
+        v |= if self.data_ready { I1_ZYXDA } else { 0 };
+        v |= if self.watermark { I1_WTM } else { 0 };
+        v |= if self.overrun { I1_OVERRUN } else { 0 };
+        v
+    }
+
+    /// Decoded from the `CTRL_REG3` routing bits
+    pub(crate) fn from_bits(v: u8) -> Self {
+        Self {
+            click: (v & I1_CLICK) != 0,
+            ia1: (v & I1_IA1) != 0,
+            ia2: (v & I1_IA2) != 0,
+            data_ready: (v & I1_ZYXDA) != 0,
+            watermark: (v & I1_WTM) != 0,
+            overrun: (v & I1_OVERRUN) != 0,
+        }
+    }
+}
+
+/// Signals routed to the `INT2` pin,
+/// see `CTRL_REG6`
+#[derive(Copy, Clone, Default)]
+pub struct Int2Routing {
+    pub click: bool,    // click interrupt (`I2_CLICK`)
+    pub ia1: bool,      // interrupt generator 1 (`I2_IA1`)
+    pub ia2: bool,      // interrupt generator 2 (`I2_IA2`)
+    pub boot: bool,     // boot status (`I2_BOOT`)
+    pub activity: bool, // sleep-to-wake activity (`I2_ACT`)
+}
+
+impl Int2Routing {
+    /// `CTRL_REG6` routing bits
+    pub(crate) fn bits(&self) -> u8 {
+        let mut v = 0;
+        v |= if self.click { I2_CLICK } else { 0 };
+        v |= if self.ia1 { I2_IA1 } else { 0 };
+        v |= if self.ia2 { I2_IA2 } else { 0 };
+        v |= if self.boot { I2_BOOT } else { 0 };
+        v |= if self.activity { I2_ACT } else { 0 };
+        v
+    }
+
+    /// Decoded from the `CTRL_REG6` routing bits
+    pub(crate) fn from_bits(v: u8) -> Self {
+        Self {
+            click: (v & I2_CLICK) != 0,
+            ia1: (v & I2_IA1) != 0,
+            ia2: (v & I2_IA2) != 0,
+            boot: (v & I2_BOOT) != 0,
+            activity: (v & I2_ACT) != 0,
+        }
+    }
+}
+
+/// Device orientation,
+/// decoded from INTx_SRC in 6D/4D position recognition mode
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Orientation {
+    /// Y axis pointing up (`YH`)
+    PortraitUp,
+    /// Y axis pointing down (`YL`)
+    PortraitDown,
+    /// X axis pointing down (`XL`)
+    LandscapeLeft,
+    /// X axis pointing up (`XH`)
+    LandscapeRight,
+    /// Z axis pointing up (`ZH`)
+    FaceUp,
+    /// Z axis pointing down (`ZL`)
+    FaceDown,
+}
+
+impl Orientation {
+    /// Position recognised by the interrupt generator, `None` if no axis is over the threshold
+    pub fn from_source(src: &InterruptSource) -> Option<Self> {
+        if !src.ia {
+            return None;
+        }
+
+        match (src.xyz_low, src.xyz_high) {
+            (_, (_, _, true)) => Some(Orientation::FaceUp),
+            ((_, _, true), _) => Some(Orientation::FaceDown),
+            (_, (_, true, _)) => Some(Orientation::PortraitUp),
+            ((_, true, _), _) => Some(Orientation::PortraitDown),
+            (_, (true, _, _)) => Some(Orientation::LandscapeRight),
+            ((true, _, _), _) => Some(Orientation::LandscapeLeft),
+            _ => None,
+        }
+    }
+}
+
+pub struct OrientationConfig {
+    pub generator: InterruptGenerator,
+    pub threshold_deg: u8, // 0-90 tilt of an axis above the horizontal before it is reported
+    pub four_d: bool,      // 4D detection, ignores the Z axis (no face up / face down)
+    pub duration: u8,      // 0-127 ODR cycles an orientation must be held
+}
+
+impl Default for OrientationConfig {
+    fn default() -> Self {
+        Self {
+            generator: InterruptGenerator::Int1,
+            threshold_deg: 50,
+            four_d: false,
+            duration: 0,
+        }
+    }
+}
+
+/// Free-fall detection configuration,
+/// an AND combination of low events on all axes
+pub struct FreeFallConfig {
+    pub generator: InterruptGenerator,
+    pub pin: InterruptPin,
+    pub threshold_mg: u16, // all axes below this acceleration (e.g 350mg)
+    pub duration_ms: u16,  // for at least this long (e.g 30ms)
+    pub latch: bool,       // keep the interrupt active until `INTx_SRC` is read
+}
+
+impl Default for FreeFallConfig {
+    fn default() -> Self {
+        Self {
+            generator: InterruptGenerator::Int1,
+            pin: InterruptPin::Int1,
+            threshold_mg: 350,
+            duration_ms: 30,
+            latch: true,
+        }
+    }
+}
+
+/// Wake-up / motion detection configuration,
+/// an OR combination of high events on the high-pass filtered data so that gravity is ignored
+pub struct MotionWakeConfig {
+    pub generator: InterruptGenerator,
+    pub pin: InterruptPin,
+    pub xyz_axes_enabled: (bool, bool, bool), // (x, y, z)
+    pub threshold_mg: u16, // any enabled axis above this acceleration (e.g 250mg)
+    pub duration_ms: u16,  // for at least this long
+    pub latch: bool,       // keep the interrupt active until `INTx_SRC` is read
+}
+
+impl Default for MotionWakeConfig {
+    fn default() -> Self {
+        Self {
+            generator: InterruptGenerator::Int1,
+            pin: InterruptPin::Int1,
+            xyz_axes_enabled: (true, true, true),
+            threshold_mg: 250,
+            duration_ms: 0,
+            latch: true,
+        }
+    }
+}
+
+/// Sleep-to-wake, return-to-sleep configuration,
+/// the device drops to low-power 10 Hz once the acceleration stays below the threshold for the duration
+/// and returns to the configured ODR and mode as soon as the threshold is exceeded
+pub struct ActivityConfig {
+    pub threshold_mg: u16,   // activation threshold (e.g 100mg)
+    pub duration_s: f32,     // time below the threshold before going to sleep
+    pub route_to_int2: bool, // signal the inactive state on the INT2 pin (`I2_ACT`)
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            threshold_mg: 100,
+            duration_s: 5.0,
+            route_to_int2: true,
+        }
+    }
+}
+
+// `TIME_LIMIT`, `TIME_LATENCY`, `TIME_WINDOW` register values
+pub(crate) type ClickTimeCycles = (u8, u8, u8);
+
+// number of ODR cycles in a duration, rounded to the nearest cycle
+fn odr_cycles(ms: u16, hz: u32) -> u32 {
+    (ms as u32 * hz + 500) / 1000
+}
+
+// `CLICK_THS` value of a threshold in mg at a scale
+pub(crate) fn click_ths(fs: FullScaleSelection, mg: u16) -> Result<u8, ConfigError> {
+    let lsb = fs.ths_lsb_mg() as u32;
+    let ths = (mg as u32 + lsb / 2) / lsb;
+    if ths > THS_MASK as u32 {
+        return Err(ConfigError::ClickOutOfRange);
+    }
+    Ok(ths as u8)
+}
+
+// `TIME_LIMIT`, `TIME_LATENCY`, `TIME_WINDOW` values of the click timing,
+// `None` in power-down mode where no clicks are detected
+pub(crate) fn click_time_cycles(
+    odr: OutputDataRate,
+    mode: OperatingMode,
+    time: &ClickTime,
+) -> Result<Option<ClickTimeCycles>, ConfigError> {
+    let hz = odr.hz(mode);
+    if hz == 0 {
+        return Ok(None);
+    }
+
+    let cycles = |ms: u16, max: u8| {
+        let c = odr_cycles(ms, hz);
+        let c = if c == 0 && ms > 0 { 1 } else { c };
+        if c > max as u32 {
+            Err(ConfigError::ClickOutOfRange)
+        } else {
+            Ok(c as u8)
         }
+    };
+
+    Ok(Some((
+        cycles(time.limit_ms, TLI_MASK)?,
+        cycles(time.latency_ms, u8::MAX)?,
+        cycles(time.window_ms, u8::MAX)?,
+    )))
+}
+
+// register values of a configuration that is allowed by the datasheet
+fn checked_registers<SpiError, PinError>(
+    config: &Config,
+) -> Result<ConfigRegisters, Error<SpiError, PinError>> {
+    config.validate().map_err(Error::InvalidConfig)?;
+    config.registers().map_err(Error::InvalidConfig)
+}
+
+// register field decoded into its enum, fails if the field value has no meaning
+fn decode<T: FromPrimitive, SpiError, PinError>(
+    register: Register,
+    value: u8,
+    field: u8,
+) -> Result<T, Error<SpiError, PinError>> {
+    T::from_u8(field).ok_or(Error::UnexpectedRegisterValue(register, value))
+}
+
+// sine of an angle in degrees (0-90) in milli-g, using Bhaskara's approximation (error < 0.2%)
+fn sin_mg(deg: u8) -> u32 {
+    let x = deg.min(90) as u32;
+    let p = x * (180 - x);
+    1000 * 4 * p / (40500 - p)
+}
+
+/// Interrupt signal that can be routed to a pin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InterruptSignal {
+    /// Click, `INT1` or `INT2`
+    Click,
+    /// Interrupt generator 1, `INT1` or `INT2`
+    Ia1,
+    /// Interrupt generator 2, `INT1` or `INT2`
+    Ia2,
+    /// New XYZ data available, `INT1` only
+    DataReady,
+    /// FIFO watermark, `INT1` only
+    Watermark,
+    /// FIFO overrun, `INT1` only
+    Overrun,
+    /// Boot status, `INT2` only
+    Boot,
+    /// Sleep-to-wake activity, `INT2` only
+    Activity,
+}
+
+const INTERRUPT_SIGNALS: [InterruptSignal; 8] = [
+    InterruptSignal::Click,
+    InterruptSignal::Ia1,
+    InterruptSignal::Ia2,
+    InterruptSignal::DataReady,
+    InterruptSignal::Watermark,
+    InterruptSignal::Overrun,
+    InterruptSignal::Boot,
+    InterruptSignal::Activity,
+];
+
+impl From<InterruptGenerator> for InterruptSignal {
+    fn from(generator: InterruptGenerator) -> Self {
+        match generator {
+            InterruptGenerator::Int1 => InterruptSignal::Ia1,
+            InterruptGenerator::Int2 => InterruptSignal::Ia2,
+        }
+    }
+}
+
+/// Interrupt routing of both pins as configured through the driver,
+/// including the settings shared between independently configured features
+#[derive(Copy, Clone, Default)]
+pub struct InterruptRouting {
+    pub int1: Int1Routing,
+    pub int2: Int2Routing,
+    pub active_low: bool,  // polarity of both pins (`INT_POLARITY`)
+    pub latch_ia1: bool,   // interrupt generator 1 latched (`LIR_INT1`)
+    pub latch_ia2: bool,   // interrupt generator 2 latched (`LIR_INT2`)
+    pub latch_click: bool, // click latched until `CLICK_SRC` is read (`LIR_Click`)
+}
+
+impl InterruptRouting {
+    /// (INT1, INT2) pins the signal is routed to
+    pub fn pins(&self, signal: InterruptSignal) -> (bool, bool) {
+        match signal {
+            InterruptSignal::Click => (self.int1.click, self.int2.click),
+            InterruptSignal::Ia1 => (self.int1.ia1, self.int2.ia1),
+            InterruptSignal::Ia2 => (self.int1.ia2, self.int2.ia2),
+            InterruptSignal::DataReady => (self.int1.data_ready, false),
+            InterruptSignal::Watermark => (self.int1.watermark, false),
+            InterruptSignal::Overrun => (self.int1.overrun, false),
+            InterruptSignal::Boot => (false, self.int2.boot),
+            InterruptSignal::Activity => (false, self.int2.activity),
+        }
+    }
+
+    /// Whether the signal is routed to any pin
+    pub fn is_routed(&self, signal: InterruptSignal) -> bool {
+        let (int1, int2) = self.pins(signal);
+        int1 || int2
+    }
+
+    /// Decoded from `CTRL_REG3`, `CTRL_REG5`, `CTRL_REG6` and `CLICK_THS`
+    pub(crate) fn from_bits(ctrl_reg3: u8, ctrl_reg5: u8, ctrl_reg6: u8, click_ths: u8) -> Self {
+        Self {
+            int1: Int1Routing::from_bits(ctrl_reg3),
+            int2: Int2Routing::from_bits(ctrl_reg6),
+            active_low: (ctrl_reg6 & INT_POLARITY) != 0,
+            latch_ia1: (ctrl_reg5 & LIR_INT1) != 0,
+            latch_ia2: (ctrl_reg5 & LIR_INT2) != 0,
+            latch_click: (click_ths & LIR_Click) != 0,
+        }
+    }
+
+    /// Latching of a signal (`LIR_Click`, `LIR_INT1`, `LIR_INT2`),
+    /// `None` for signals that cannot be latched
+    pub fn latch(&self, signal: InterruptSignal) -> Option<bool> {
+        match signal {
+            InterruptSignal::Click => Some(self.latch_click),
+            InterruptSignal::Ia1 => Some(self.latch_ia1),
+            InterruptSignal::Ia2 => Some(self.latch_ia2),
+            _ => None,
+        }
+    }
+
+    // whether any signal other than the one given is routed to a pin
+    fn others_routed(&self, signal: Option<InterruptSignal>) -> bool {
+        INTERRUPT_SIGNALS
+            .iter()
+            .any(|s| Some(*s) != signal && self.is_routed(*s))
+    }
+
+    // returns false if the signal is not available on the pin
+    fn set(&mut self, signal: InterruptSignal, pin: InterruptPin, enable: bool) -> bool {
+        let bit = match (pin, signal) {
+            (InterruptPin::Int1, InterruptSignal::Click) => &mut self.int1.click,
+            (InterruptPin::Int1, InterruptSignal::Ia1) => &mut self.int1.ia1,
+            (InterruptPin::Int1, InterruptSignal::Ia2) => &mut self.int1.ia2,
+            (InterruptPin::Int1, InterruptSignal::DataReady) => &mut self.int1.data_ready,
+            (InterruptPin::Int1, InterruptSignal::Watermark) => &mut self.int1.watermark,
+            (InterruptPin::Int1, InterruptSignal::Overrun) => &mut self.int1.overrun,
+            (InterruptPin::Int2, InterruptSignal::Click) => &mut self.int2.click,
+            (InterruptPin::Int2, InterruptSignal::Ia1) => &mut self.int2.ia1,
+            (InterruptPin::Int2, InterruptSignal::Ia2) => &mut self.int2.ia2,
+            (InterruptPin::Int2, InterruptSignal::Boot) => &mut self.int2.boot,
+            (InterruptPin::Int2, InterruptSignal::Activity) => &mut self.int2.activity,
+            _ => return false,
+        };
+        *bit = enable;
+        true
     }
 }
 
-impl<SPI, SpiError, CS, PinError> Lis2dh12<SPI, CS>
+/// Interrupt events,
+/// decoded from the source registers of the signals routed to a pin
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InterruptEvents {
+    /// `CLICK_SRC` when a click was detected
+    pub click: Option<ClickEvent>,
+    /// `INT1_SRC` when interrupt generator 1 is active
+    pub ia1: Option<InterruptSource>,
+    /// `INT2_SRC` when interrupt generator 2 is active
+    pub ia2: Option<InterruptSource>,
+    /// `STATUS_REG`: `ZYXDA` (INT1 only)
+    pub data_ready: bool,
+    /// `FIFO_SRC_REG`: `WTM` (INT1 only)
+    pub watermark: bool,
+    /// `FIFO_SRC_REG`: `OVRN_FIFO` (INT1 only)
+    pub overrun: bool,
+    /// Activity is routed and no other source explains the interrupt (INT2 only)
+    pub activity: bool,
+}
+
+/// Async interrupt input pin,
+/// the driver waits for a level when the interrupt is latched and for an edge otherwise
+pub trait InterruptInput:
+    WaitForHigh + WaitForLow + WaitForRisingEdge + WaitForFallingEdge
+{
+}
+
+impl<T> InterruptInput for T where
+    T: WaitForHigh + WaitForLow + WaitForRisingEdge + WaitForFallingEdge
+{
+}
+
+impl<SPI, SpiError, CS, PinError, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2>
 where
     SPI: FullDuplex<u8, Error = SpiError>,
     CS: OutputPin<Error = PinError>,
@@ -141,13 +702,79 @@ where
         Ok(())
     }
 
+    /// Sleep-to-wake, return-to-sleep in physical units,
+    /// `ACT_THS`: `Acth` derived from the current scale,
+    /// `ACT_DUR`: `ActD` derived from the current output data rate as duration = (8 * ActD + 1) / ODR,
+    /// `CTRL_REG6`: `I2_ACT`,
+    /// fails with `OutOfRange` for a negative, non-finite or too long duration
+    pub async fn enable_activity(
+        &mut self,
+        config: &ActivityConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let ths = self.ths_from_mg(config.threshold_mg)?;
+        let hz = self.odr.hz(self.mode);
+        if hz == 0 || !config.duration_s.is_finite() || config.duration_s < 0.0 {
+            return Err(Error::OutOfRange);
+        }
+
+        let d = (config.duration_s * hz as f32 - 1.0) / 8.0;
+        let d = if d > 0.0 { (d + 0.5) as u32 } else { 0 };
+        if d > u8::MAX as u32 {
+            return Err(Error::OutOfRange);
+        }
+
+        self.set_act_ths(ths).await?;
+        self.set_act_dur(d as u8).await?;
+
+        self.route(
+            InterruptSignal::Activity,
+            InterruptPin::Int2,
+            config.route_to_int2,
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Block data update,
     /// `CTRL_REG4`: `BDU`
     pub async fn set_bdu(&mut self, bdu: bool) -> Result<(), Error<SpiError, PinError>> {
+        if !bdu && (self.read_reg(Register::TEMP_CFG_REG).await? & TEMP_EN) != 0 {
+            return Err(Error::InvalidConfig(ConfigError::TemperatureRequiresBdu));
+        }
         self.reg_xset_bits(Register::CTRL_REG4, BDU, bdu).await?;
         Ok(())
     }
 
+    /// Big/little endian data selection, only available in high-resolution mode,
+    /// `get_raw` expects little endian data (the default),
+    /// `CTRL_REG4`: `BLE`
+    pub async fn set_ble(&mut self, big_endian: bool) -> Result<(), Error<SpiError, PinError>> {
+        if big_endian && !matches!(self.mode, OperatingMode::HighResolution) {
+            return Err(Error::InvalidConfig(ConfigError::BleRequiresHighResolution));
+        }
+        self.reg_xset_bits(Register::CTRL_REG4, BLE, big_endian)
+            .await?;
+        Ok(())
+    }
+
+    /// Disconnect the pull-up on the SDO/SA0 pin,
+    /// the fixed bits of the register are always written with their mandatory value,
+    /// `CTRL_REG0`: `SDO_PU_DISC`
+    pub async fn disconnect_sdo_pull_up(
+        &mut self,
+        disconnect: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let pu = if disconnect { SDO_PU_DISC } else { 0 };
+        self.write_reg(Register::CTRL_REG0, pu | CTRL_REG0_FIXED)
+            .await
+    }
+
+    /// Pull-up on the SDO/SA0 pin is disconnected,
+    /// `CTRL_REG0`: `SDO_PU_DISC`
+    pub async fn is_sdo_pull_up_disconnected(&mut self) -> Result<bool, Error<SpiError, PinError>> {
+        Ok(self.read_reg(Register::CTRL_REG0).await? & SDO_PU_DISC != 0)
+    }
+
     /// Temperature sensor enable,
     /// `TEMP_CFG_REG`: `TEMP_EN`,
     /// the `BDU` bit in `CTRL_REG4` is also set
@@ -179,10 +806,16 @@ where
         &mut self,
         odr: OutputDataRate,
     ) -> Result<(), Error<SpiError, PinError>> {
+        check_mode(odr, self.mode).map_err(Error::InvalidConfig)?;
+        let click_time = self.click_time_cycles_at(odr, self.mode)?;
         self.modify_reg(Register::CTRL_REG1, |v| {
             (v & !ODR_MASK) | ((odr as u8) << 4)
         })
         .await?;
+        self.odr = odr;
+        if let Some((limit, latency, window)) = click_time {
+            self.set_click_time(limit, latency, window).await?;
+        }
         // By design, when the device from high-resolution configuration (HR) is set to power-down mode (PD),
         // it is recommended to read register REFERENCE (26h) for a complete reset of the filtering block
         // before switching to normal/high-performance mode again.
@@ -192,11 +825,70 @@ where
         Ok(())
     }
 
+    /// High-pass filter configuration,
+    /// `CTRL_REG2`: `HPM`, `HPCF`, `FDS`, `HPCLICK`, `HP_IA2`, `HP_IA1`,
+    /// `REFERENCE` is read afterwards to reset the filter
+    pub async fn set_high_pass(
+        &mut self,
+        config: &HighPassConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.write_reg(Register::CTRL_REG2, config.bits()).await?;
+        self.get_ref().await?;
+        Ok(())
+    }
+
+    /// High-pass filter cutoff frequency in Hz at the current output data rate
+    pub fn get_high_pass_cutoff_hz(&self, cutoff: HighPassCutoff) -> f32 {
+        cutoff.hz(self.odr, self.mode)
+    }
+
     /// `REFERENCE` register
     pub async fn get_ref(&mut self) -> Result<u8, Error<SpiError, PinError>> {
         self.read_reg(Register::REFERENCE).await
     }
 
+    /// `REFERENCE` register,
+    /// used by the high-pass filter in `HighPassMode::Reference`
+    pub async fn set_ref(&mut self, reference: u8) -> Result<(), Error<SpiError, PinError>> {
+        self.write_reg(Register::REFERENCE, reference).await
+    }
+
+    /// Reference in mg for the high-pass filter in `HighPassMode::Reference`,
+    /// `REFERENCE` derived from the current scale
+    pub async fn set_reference_mg(&mut self, mg: i16) -> Result<(), Error<SpiError, PinError>> {
+        let lsb = self.scale.ths_lsb_mg() as i32;
+        let mg = mg as i32;
+        let reference = if mg < 0 {
+            (mg - lsb / 2) / lsb
+        } else {
+            (mg + lsb / 2) / lsb
+        };
+        if !(i8::MIN as i32..=i8::MAX as i32).contains(&reference) {
+            return Err(Error::OutOfRange);
+        }
+        self.set_ref(reference as i8 as u8).await
+    }
+
+    /// Capture the current acceleration of an axis as the high-pass filter reference,
+    /// the 8 most significant bits of `OUT_x_H` are written to `REFERENCE`,
+    /// returns the captured reference in mg
+    pub async fn capture_reference(
+        &mut self,
+        axis: Axis,
+    ) -> Result<i16, Error<SpiError, PinError>> {
+        let raw = self.get_raw().await?;
+        let value = match axis {
+            Axis::X => raw.x,
+            Axis::Y => raw.y,
+            Axis::Z => raw.z,
+        };
+
+        // left aligned output, the high byte has the same resolution as the reference
+        let reference = (value >> 8) as i8;
+        self.set_ref(reference as u8).await?;
+        Ok(reference as i16 * self.scale.ths_lsb_mg() as i16)
+    }
+
     /*
         pub async fn set_low_power_mode(
             &mut self,
@@ -326,6 +1018,15 @@ where
         &mut self,
         mode: OperatingMode,
     ) -> Result<(), Error<SpiError, PinError>> {
+        check_mode(self.odr, mode).map_err(Error::InvalidConfig)?;
+        if !matches!(mode, OperatingMode::HighResolution)
+            && (self.read_reg(Register::CTRL_REG4).await? & BLE) != 0
+        {
+            return Err(Error::InvalidConfig(ConfigError::BleRequiresHighResolution));
+        }
+
+        // HighRate1 runs faster in low-power mode which changes the click time
+        let click_time = self.click_time_cycles_at(self.odr, mode)?;
         match mode {
             OperatingMode::LowPower => {
                 self.reg_reset_bits(Register::CTRL_REG4, HR).await?;
@@ -340,6 +1041,10 @@ where
                 self.reg_set_bits(Register::CTRL_REG4, HR).await?;
             }
         }
+        self.mode = mode;
+        if let Some((limit, latency, window)) = click_time {
+            self.set_click_time(limit, latency, window).await?;
+        }
         Ok(())
     }
 
@@ -354,45 +1059,561 @@ where
         ))
     }
 
-    /// Full-scale selection,
-    /// `CTRL_REG4`: `FS`
-    pub async fn set_scale(
+    /// Reboot memory content, reloads the trimming parameters,
+    /// waits for `BOOT` to clear,
+    /// `CTRL_REG5`: `BOOT`
+    pub async fn reboot_memory<D: Delay>(
         &mut self,
-        fs: FullScaleSelection,
+        delay: &mut D,
     ) -> Result<(), Error<SpiError, PinError>> {
-        self.modify_reg(Register::CTRL_REG4, |v| (v & !FS_MASK) | ((fs as u8) << 4))
-            .await?;
-        self.scale = fs;
+        self.reg_set_bits(Register::CTRL_REG5, BOOT).await?;
+        for _ in 0..10 {
+            delay.delay_ms(5).await;
+            if self.read_reg(Register::CTRL_REG5).await? & BOOT == 0 {
+                return Ok(());
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Write the datasheet reset values to all writable registers,
+    /// latched interrupts are cleared and the cached scale, data rate, mode,
+    /// routing and click settings are reset as well
+    pub async fn reset_to_defaults(&mut self) -> Result<(), Error<SpiError, PinError>> {
+        for (reg, value) in RESET_VALUES.iter() {
+            self.write_reg(*reg, *value).await?;
+        }
+        self.get_ref().await?;
+        self.read_reg(Register::INT1_SRC).await?;
+        self.read_reg(Register::INT2_SRC).await?;
+        self.read_reg(Register::CLICK_SRC).await?;
+
+        self.scale = FullScaleSelection::PlusMinus2G;
+        self.odr = OutputDataRate::PowerDown;
+        self.mode = OperatingMode::Normal;
+        self.routing = InterruptRouting::default();
+        self.click_threshold_mg = None;
+        self.click_time = None;
         Ok(())
     }
 
-    /// X,Y,Z-axis enable,
-    /// `CTRL_REG1`: `Xen`, `Yen`, `Zen`
-    pub async fn enable_axis(
-        &mut self,
-        (x, y, z): (bool, bool, bool),
-    ) -> Result<(), Error<SpiError, PinError>> {
-        self.modify_reg(Register::CTRL_REG1, |mut v| {
-            v &= !(Xen | Yen | Zen); // disable all axises
-            v |= if x { Xen } else { 0 };
-            v |= if y { Yen } else { 0 };
-            v |= if z { Zen } else { 0 };
-            v
-        })
+    /// Write a complete configuration,
+    /// the device is powered down while the other registers are written in bursts,
+    /// `CTRL_REG1` is written last and `REFERENCE` is read to reset the high-pass filter,
+    /// fails with `InvalidConfig` before anything is written if the configuration is not allowed
+    /// or cannot be converted to register values
+    pub async fn apply(&mut self, config: &Config) -> Result<(), Error<SpiError, PinError>> {
+        let regs = checked_registers(config)?;
+
+        self.write_reg(Register::CTRL_REG1, regs.ctrl_reg1 & !ODR_MASK)
+            .await?;
+        self.write_reg(Register::TEMP_CFG_REG, regs.temp_cfg_reg)
+            .await?;
+        self.write_regs(
+            Register::CTRL_REG2,
+            &[
+                regs.ctrl_reg2,
+                regs.ctrl_reg3,
+                regs.ctrl_reg4,
+                regs.ctrl_reg5,
+                regs.ctrl_reg6,
+            ],
+        )
+        .await?;
+        self.write_reg(Register::FIFO_CTRL_REG, regs.fifo_ctrl_reg)
+            .await?;
+        // the source registers in between are read-only
+        self.write_reg(Register::INT1_CFG, regs.int1_cfg).await?;
+        self.write_regs(Register::INT1_THS, &[regs.int1_ths, regs.int1_duration])
+            .await?;
+        self.write_reg(Register::INT2_CFG, regs.int2_cfg).await?;
+        self.write_regs(Register::INT2_THS, &[regs.int2_ths, regs.int2_duration])
+            .await?;
+        self.write_reg(Register::CLICK_CFG, regs.click_cfg).await?;
+        self.write_regs(
+            Register::CLICK_THS,
+            &[
+                regs.click_ths,
+                regs.time_limit,
+                regs.time_latency,
+                regs.time_window,
+            ],
+        )
         .await?;
+        self.write_reg(Register::CTRL_REG1, regs.ctrl_reg1).await?;
+        self.get_ref().await?;
+
+        self.odr = config.odr;
+        self.mode = config.mode;
+        self.scale = config.scale;
+        self.routing = config.routing;
+        self.click_threshold_mg = config.click.map(|click| click.threshold_mg);
+        self.click_time = config.click.map(|click| click.time);
         Ok(())
     }
 
-    /// Get normalized ±g reading from the accelerometer
-    pub async fn get_norm(&mut self) -> Result<F32x3, Error<SpiError, PinError>> {
-        let acc_raw: I16x3 = self.get_raw().await?;
+    /// Read the configuration back from the device,
+    /// the source registers are skipped so that latched interrupts are not cleared,
+    /// the cached scale, data rate, mode, routing and click settings are resynchronised
+    pub async fn read_config(&mut self) -> Result<Config, Error<SpiError, PinError>> {
+        let regs = self.read_config_registers().await?;
+        let config = self.config_from_registers(&regs)?;
+
+        self.odr = config.odr;
+        self.mode = config.mode;
+        self.scale = config.scale;
+        self.routing = config.routing;
+        self.click_threshold_mg = config.click.map(|click| click.threshold_mg);
+        self.click_time = config.click.map(|click| click.time);
+        Ok(config)
+    }
 
-        let sensitivity: f32 = match self.scale {
-            FullScaleSelection::PlusMinus2G => 0.001,
-            FullScaleSelection::PlusMinus4G => 0.002,
-            FullScaleSelection::PlusMinus8G => 0.004,
-            FullScaleSelection::PlusMinus16G => 0.012,
-        };
+    /// Register values of the current configuration
+    pub async fn read_config_registers(
+        &mut self,
+    ) -> Result<ConfigRegisters, Error<SpiError, PinError>> {
+        let mut ctrl = [0u8; 7]; // TEMP_CFG_REG, CTRL_REG1..CTRL_REG6
+        self.read_regs(Register::TEMP_CFG_REG, &mut ctrl).await?;
+        let mut int1 = [0u8; 2];
+        self.read_regs(Register::INT1_THS, &mut int1).await?;
+        let mut int2 = [0u8; 2];
+        self.read_regs(Register::INT2_THS, &mut int2).await?;
+        let mut click = [0u8; 4];
+        self.read_regs(Register::CLICK_THS, &mut click).await?;
+
+        Ok(ConfigRegisters {
+            temp_cfg_reg: ctrl[0],
+            ctrl_reg1: ctrl[1],
+            ctrl_reg2: ctrl[2],
+            ctrl_reg3: ctrl[3],
+            ctrl_reg4: ctrl[4],
+            ctrl_reg5: ctrl[5],
+            ctrl_reg6: ctrl[6],
+            fifo_ctrl_reg: self.read_reg(Register::FIFO_CTRL_REG).await?,
+            int1_cfg: self.read_reg(Register::INT1_CFG).await?,
+            int1_ths: int1[0],
+            int1_duration: int1[1],
+            int2_cfg: self.read_reg(Register::INT2_CFG).await?,
+            int2_ths: int2[0],
+            int2_duration: int2[1],
+            click_cfg: self.read_reg(Register::CLICK_CFG).await?,
+            click_ths: click[0],
+            time_limit: click[1],
+            time_latency: click[2],
+            time_window: click[3],
+        })
+    }
+
+    /// Configuration decoded from register values,
+    /// the click time cannot be recovered in power-down mode and is taken from the cache instead
+    pub fn config_from_registers(
+        &self,
+        regs: &ConfigRegisters,
+    ) -> Result<Config, Error<SpiError, PinError>> {
+        let odr: OutputDataRate = decode(Register::CTRL_REG1, regs.ctrl_reg1, regs.ctrl_reg1 >> 4)?;
+        let mode = Self::decode_operating_mode(regs.ctrl_reg1, regs.ctrl_reg4)?;
+        let scale: FullScaleSelection = decode(
+            Register::CTRL_REG4,
+            regs.ctrl_reg4,
+            (regs.ctrl_reg4 & FS_MASK) >> 4,
+        )?;
+
+        let high_pass = Self::decode_high_pass(regs.ctrl_reg2)?;
+        let fifo = Self::decode_fifo(regs.ctrl_reg5, regs.fifo_ctrl_reg)?;
+
+        let routing = InterruptRouting::from_bits(
+            regs.ctrl_reg3,
+            regs.ctrl_reg5,
+            regs.ctrl_reg6,
+            regs.click_ths,
+        );
+
+        let ia1 = Self::decode_inertial(
+            Register::INT1_CFG,
+            regs.int1_cfg,
+            regs.int1_ths,
+            regs.int1_duration,
+        )?;
+        let ia2 = Self::decode_inertial(
+            Register::INT2_CFG,
+            regs.int2_cfg,
+            regs.int2_ths,
+            regs.int2_duration,
+        )?;
+
+        let click = if (regs.click_cfg & (XD | XS | YD | YS | ZD | ZS)) != 0 {
+            let hz = odr.hz(mode);
+            let ms = |cycles: u8| ((cycles as u32 * 1000 + hz / 2) / hz) as u16;
+            let time = if hz == 0 {
+                self.click_time.unwrap_or_default()
+            } else {
+                ClickTime {
+                    limit_ms: ms(regs.time_limit & TLI_MASK),
+                    latency_ms: ms(regs.time_latency),
+                    window_ms: ms(regs.time_window),
+                }
+            };
+            let ths = (regs.click_ths & THS_MASK) as u16;
+            Some(ClickDetection {
+                source: if (regs.click_cfg & (XD | YD | ZD)) != 0 {
+                    ClickSource::Double
+                } else {
+                    ClickSource::Single
+                },
+                xyz_axes_enabled: (
+                    (regs.click_cfg & (XD | XS)) != 0,
+                    (regs.click_cfg & (YD | YS)) != 0,
+                    (regs.click_cfg & (ZD | ZS)) != 0,
+                ),
+                threshold_mg: ths * scale.ths_lsb_mg(),
+                time,
+            })
+        } else {
+            None
+        };
+
+        Ok(Config {
+            odr,
+            mode,
+            scale,
+            xyz_axes_enabled: (
+                (regs.ctrl_reg1 & Xen) != 0,
+                (regs.ctrl_reg1 & Yen) != 0,
+                (regs.ctrl_reg1 & Zen) != 0,
+            ),
+            bdu: (regs.ctrl_reg4 & BDU) != 0,
+            temperature: (regs.temp_cfg_reg & TEMP_EN) == TEMP_EN,
+            high_pass,
+            fifo,
+            routing,
+            ia1,
+            ia2,
+            four_d_ia1: (regs.ctrl_reg5 & D4D_INT1) != 0,
+            four_d_ia2: (regs.ctrl_reg5 & D4D_INT2) != 0,
+            click,
+        })
+    }
+
+    /// Operating mode,
+    /// `CTRL_REG1`: `LPen`, `CTRL_REG4`: `HR`
+    fn decode_operating_mode(
+        ctrl_reg1: u8,
+        ctrl_reg4: u8,
+    ) -> Result<OperatingMode, Error<SpiError, PinError>> {
+        match ((ctrl_reg1 & LPen) != 0, (ctrl_reg4 & HR) != 0) {
+            (false, false) => Ok(OperatingMode::Normal),
+            (true, false) => Ok(OperatingMode::LowPower),
+            (false, true) => Ok(OperatingMode::HighResolution),
+            (true, true) => Err(Error::InvalidConfig(ConfigError::HighResolutionAndLowPower)),
+        }
+    }
+
+    /// High-pass filter configuration,
+    /// `CTRL_REG2`: `HPM`, `HPCF`, `FDS`, `HPCLICK`, `HP_IA2`, `HP_IA1`
+    fn decode_high_pass(ctrl_reg2: u8) -> Result<HighPassConfig, Error<SpiError, PinError>> {
+        HighPassConfig::from_bits(ctrl_reg2).ok_or(Error::UnexpectedRegisterValue(
+            Register::CTRL_REG2,
+            ctrl_reg2,
+        ))
+    }
+
+    /// FIFO configuration, `None` when the FIFO is disabled,
+    /// `CTRL_REG5`: `FIFO_EN`, `FIFO_CTRL_REG`: `FM`, `TR`, `FTH`
+    fn decode_fifo(
+        ctrl_reg5: u8,
+        fifo_ctrl_reg: u8,
+    ) -> Result<Option<FifoConfig>, Error<SpiError, PinError>> {
+        if (ctrl_reg5 & FIFO_EN) == 0 {
+            return Ok(None);
+        }
+        match FifoConfig::from_bits(fifo_ctrl_reg) {
+            Some(fifo) => Ok(Some(fifo)),
+            None => Err(Error::UnexpectedRegisterValue(
+                Register::FIFO_CTRL_REG,
+                fifo_ctrl_reg,
+            )),
+        }
+    }
+
+    /// Inertial interrupt generator configuration,
+    /// `INTx_CFG`, `INTx_THS`, `INTx_DURATION`
+    fn decode_inertial(
+        register: Register,
+        cfg: u8,
+        ths: u8,
+        duration: u8,
+    ) -> Result<InertialInterruptConfig, Error<SpiError, PinError>> {
+        InertialInterruptConfig::from_bits(cfg, ths, duration)
+            .ok_or(Error::UnexpectedRegisterValue(register, cfg))
+    }
+
+    /// Output data rate, also resynchronises the cached value,
+    /// `CTRL_REG1`: `ODR`
+    pub async fn get_output_data_rate(
+        &mut self,
+    ) -> Result<OutputDataRate, Error<SpiError, PinError>> {
+        let v = self.read_reg(Register::CTRL_REG1).await?;
+        self.odr = decode(Register::CTRL_REG1, v, v >> 4)?;
+        Ok(self.odr)
+    }
+
+    /// Operating mode, also resynchronises the cached value,
+    /// `CTRL_REG1`: `LPen`, `CTRL_REG4`: `HR`
+    pub async fn get_operating_mode(&mut self) -> Result<OperatingMode, Error<SpiError, PinError>> {
+        let ctrl_reg1 = self.read_reg(Register::CTRL_REG1).await?;
+        let ctrl_reg4 = self.read_reg(Register::CTRL_REG4).await?;
+        self.mode = Self::decode_operating_mode(ctrl_reg1, ctrl_reg4)?;
+        Ok(self.mode)
+    }
+
+    /// Full-scale selection, also resynchronises the cached value,
+    /// `CTRL_REG4`: `FS`
+    pub async fn get_scale(&mut self) -> Result<FullScaleSelection, Error<SpiError, PinError>> {
+        let v = self.read_reg(Register::CTRL_REG4).await?;
+        self.scale = decode(Register::CTRL_REG4, v, (v & FS_MASK) >> 4)?;
+        Ok(self.scale)
+    }
+
+    /// X,Y,Z-axis enabled,
+    /// `CTRL_REG1`: `Xen`, `Yen`, `Zen`
+    pub async fn get_enabled_axes(
+        &mut self,
+    ) -> Result<(bool, bool, bool), Error<SpiError, PinError>> {
+        let v = self.read_reg(Register::CTRL_REG1).await?;
+        Ok(((v & Xen) != 0, (v & Yen) != 0, (v & Zen) != 0))
+    }
+
+    /// Block data update,
+    /// `CTRL_REG4`: `BDU`
+    pub async fn get_bdu(&mut self) -> Result<bool, Error<SpiError, PinError>> {
+        Ok((self.read_reg(Register::CTRL_REG4).await? & BDU) != 0)
+    }
+
+    /// High-pass filter configuration,
+    /// `CTRL_REG2`: `HPM`, `HPCF`, `FDS`, `HPCLICK`, `HP_IA2`, `HP_IA1`
+    pub async fn get_high_pass(&mut self) -> Result<HighPassConfig, Error<SpiError, PinError>> {
+        let ctrl_reg2 = self.read_reg(Register::CTRL_REG2).await?;
+        Self::decode_high_pass(ctrl_reg2)
+    }
+
+    /// FIFO configuration, `None` when the FIFO is disabled,
+    /// `CTRL_REG5`: `FIFO_EN`, `FIFO_CTRL_REG`: `FM`, `TR`, `FTH`
+    pub async fn get_fifo(&mut self) -> Result<Option<FifoConfig>, Error<SpiError, PinError>> {
+        let ctrl_reg5 = self.read_reg(Register::CTRL_REG5).await?;
+        let fifo_ctrl_reg = self.read_reg(Register::FIFO_CTRL_REG).await?;
+        Self::decode_fifo(ctrl_reg5, fifo_ctrl_reg)
+    }
+
+    /// Inertial interrupt generator configuration,
+    /// `INTx_CFG`, `INTx_THS`, `INTx_DURATION`
+    pub async fn get_inertial_interrupt(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<InertialInterruptConfig, Error<SpiError, PinError>> {
+        let cfg = self.read_reg(generator.cfg()).await?;
+        let ths = self.read_reg(generator.ths()).await?;
+        let duration = self.read_reg(generator.duration()).await?;
+        Self::decode_inertial(generator.cfg(), cfg, ths, duration)
+    }
+
+    /// Interrupt routing, polarity and latching as configured on the device,
+    /// also resynchronises the cached routing,
+    /// `CTRL_REG3`, `CTRL_REG5`: `LIR_INT1`, `LIR_INT2`, `CTRL_REG6`, `CLICK_THS`: `LIR_Click`
+    pub async fn read_routing(&mut self) -> Result<InterruptRouting, Error<SpiError, PinError>> {
+        let ctrl_reg3 = self.read_reg(Register::CTRL_REG3).await?;
+        let ctrl_reg5 = self.read_reg(Register::CTRL_REG5).await?;
+        let ctrl_reg6 = self.read_reg(Register::CTRL_REG6).await?;
+        let click_ths = self.read_reg(Register::CLICK_THS).await?;
+        self.routing = InterruptRouting::from_bits(ctrl_reg3, ctrl_reg5, ctrl_reg6, click_ths);
+        Ok(self.routing)
+    }
+
+    /// Read all mapped registers,
+    /// note that this has the read side effects of `Register::read_has_side_effects`:
+    /// latched interrupts are cleared and the high-pass filter is reset
+    pub async fn dump_registers(&mut self) -> Result<RegisterDump, Error<SpiError, PinError>> {
+        let mut values = [0u8; REGISTERS.len()];
+        let (single, burst) = values.split_at_mut(4);
+        // STATUS_REG_AUX, OUT_TEMP_L, OUT_TEMP_H, WHO_AM_I are not contiguous
+        for (value, register) in single.iter_mut().zip(REGISTERS.iter()) {
+            *value = self.read_reg(*register).await?;
+        }
+        // CTRL_REG0 (1Eh) to ACT_DUR (3Fh)
+        self.read_regs(Register::CTRL_REG0, burst).await?;
+        Ok(RegisterDump::new(values))
+    }
+
+    /// Self-test mode,
+    /// `CTRL_REG4`: `ST`
+    pub async fn set_self_test(&mut self, st: SelfTest) -> Result<(), Error<SpiError, PinError>> {
+        self.modify_reg(Register::CTRL_REG4, |v| (v & !ST_MASK) | ((st as u8) << 1))
+            .await?;
+        Ok(())
+    }
+
+    /// Self-test following the datasheet procedure,
+    /// the output is averaged over `samples` with the self-test disabled and enabled (50 Hz, normal mode, ±2g),
+    /// fails with `OutOfRange` for `SelfTest::Disabled`,
+    /// `CTRL_REG1` to `CTRL_REG4` are restored afterwards even if the test failed,
+    /// an error of the test takes precedence over the first error of the restore
+    pub async fn self_test<D: Delay>(
+        &mut self,
+        delay: &mut D,
+        st: SelfTest,
+        samples: u8,
+    ) -> Result<SelfTestReport, Error<SpiError, PinError>> {
+        if matches!(st, SelfTest::Disabled) {
+            return Err(Error::OutOfRange);
+        }
+
+        let mut saved = [0u8; 4];
+        self.read_regs(Register::CTRL_REG1, &mut saved).await?;
+
+        let result = self.run_self_test(delay, st, samples.max(1)).await;
+
+        // attempt every write, the data rate last
+        let mut restored = Ok(());
+        for (register, value) in [
+            (Register::CTRL_REG4, saved[3]),
+            (Register::CTRL_REG3, saved[2]),
+            (Register::CTRL_REG2, saved[1]),
+            (Register::CTRL_REG1, saved[0]),
+        ]
+        .iter()
+        {
+            let written = self.write_reg(*register, *value).await;
+            restored = restored.and(written);
+        }
+        let reference = self.get_ref().await.map(|_| ());
+        restored = restored.and(reference);
+
+        let report = result?;
+        restored?;
+        Ok(report)
+    }
+
+    async fn run_self_test<D: Delay>(
+        &mut self,
+        delay: &mut D,
+        st: SelfTest,
+        samples: u8,
+    ) -> Result<SelfTestReport, Error<SpiError, PinError>> {
+        self.write_reg(Register::CTRL_REG2, 0x00).await?;
+        self.write_reg(Register::CTRL_REG3, 0x00).await?;
+        self.write_reg(Register::CTRL_REG4, BDU).await?;
+        self.write_reg(
+            Register::CTRL_REG1,
+            ((OutputDataRate::Hz50 as u8) << 4) | Xen | Yen | Zen,
+        )
+        .await?;
+
+        delay.delay_ms(90).await;
+        let (x0, y0, z0) = self.get_average(delay, samples).await?;
+
+        self.write_reg(Register::CTRL_REG4, BDU | ((st as u8) << 1))
+            .await?;
+        delay.delay_ms(90).await;
+        let (x1, y1, z1) = self.get_average(delay, samples).await?;
+
+        let delta = (
+            (x1 - x0).abs() as i16,
+            (y1 - y0).abs() as i16,
+            (z1 - z0).abs() as i16,
+        );
+        let within = |d: i16| (SELF_TEST_MIN..=SELF_TEST_MAX).contains(&d);
+        Ok(SelfTestReport {
+            delta,
+            pass: (within(delta.0), within(delta.1), within(delta.2)),
+        })
+    }
+
+    /// Average of the next samples in 10-bit LSB, the first sample is discarded
+    async fn get_average<D: Delay>(
+        &mut self,
+        delay: &mut D,
+        samples: u8,
+    ) -> Result<(i32, i32, i32), Error<SpiError, PinError>> {
+        self.wait_data_ready(delay).await?;
+        self.get_raw().await?;
+
+        let (mut x, mut y, mut z) = (0i32, 0i32, 0i32);
+        for _ in 0..samples {
+            self.wait_data_ready(delay).await?;
+            let raw = self.get_raw().await?;
+            x += (raw.x >> 6) as i32; // 10-bit data
+            y += (raw.y >> 6) as i32;
+            z += (raw.z >> 6) as i32;
+        }
+
+        let n = samples as i32;
+        Ok((x / n, y / n, z / n))
+    }
+
+    /// Wait for new XYZ data,
+    /// `STATUS_REG`: `ZYXDA`
+    async fn wait_data_ready<D: Delay>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        for _ in 0..100 {
+            if self.get_status().await?.zyxda {
+                return Ok(());
+            }
+            delay.delay_ms(5).await;
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Full-scale selection,
+    /// `CTRL_REG4`: `FS`
+    pub async fn set_scale(
+        &mut self,
+        fs: FullScaleSelection,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        // the click threshold is relative to the full scale so it is re-applied at the new scale
+        let click_ths = match self.click_threshold_mg {
+            Some(mg) => Some(Self::click_ths_from_mg(fs, mg)?),
+            None => None,
+        };
+
+        self.modify_reg(Register::CTRL_REG4, |v| (v & !FS_MASK) | ((fs as u8) << 4))
+            .await?;
+        self.scale = fs;
+
+        if let Some(ths) = click_ths {
+            self.set_click_threshold(ths, self.routing.latch_click)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// X,Y,Z-axis enable,
+    /// `CTRL_REG1`: `Xen`, `Yen`, `Zen`
+    pub async fn enable_axis(
+        &mut self,
+        (x, y, z): (bool, bool, bool),
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.modify_reg(Register::CTRL_REG1, |mut v| {
+            v &= !(Xen | Yen | Zen); // disable all axises
+            v |= if x { Xen } else { 0 };
+            v |= if y { Yen } else { 0 };
+            v |= if z { Zen } else { 0 };
+            v
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Get normalized ±g reading from the accelerometer
+    pub async fn get_norm(&mut self) -> Result<F32x3, Error<SpiError, PinError>> {
+        let acc_raw: I16x3 = self.get_raw().await?;
+
+        let sensitivity: f32 = match self.scale {
+            FullScaleSelection::PlusMinus2G => 0.001,
+            FullScaleSelection::PlusMinus4G => 0.002,
+            FullScaleSelection::PlusMinus8G => 0.004,
+            FullScaleSelection::PlusMinus16G => 0.012,
+        };
 
         Ok(F32x3::new(
             (acc_raw.x >> 4) as f32 * sensitivity, // 12-bit data
@@ -465,12 +1686,40 @@ where
         Ok((value as f32 * 0.25) + 25.0)
     }
 
-    /// Enable interrupt double-click on X,Y,Z axis,
-    /// `CLICK_CFG`: `XD`, `YD`, `ZD`
+    /// Enable interrupt single or double-click on X,Y,Z axis,
+    /// the click is routed to the chosen pin only, `ClickInterupt::None` unroutes it,
+    /// `CLICK_CFG`: `XS`, `YS`, `ZS` or `XD`, `YD`, `ZD`
     pub async fn enable_click(
         &mut self,
         config: ClickConfig,
     ) -> Result<(), Error<SpiError, PinError>> {
+        let ths = Self::click_ths_from_mg(self.scale, config.threshold_mg)?;
+        let click_time = Self::click_time_cycles(self.odr, self.mode, &config.time)?;
+        let interrupt = match &config.interrupt {
+            ClickInterupt::Int1(int_config) => Some((InterruptPin::Int1, int_config)),
+            ClickInterupt::Int2(int_config) => Some((InterruptPin::Int2, int_config)),
+            ClickInterupt::None => None,
+        };
+        let latch = match interrupt {
+            Some((_, int_config)) => {
+                self.check_polarity(Some(InterruptSignal::Click), int_config.active_low)?;
+                self.check_lir(InterruptSignal::Click, int_config.latch)?;
+                int_config.latch
+            }
+            None => false,
+        };
+
+        // unroute the click from a pin it is no longer meant for
+        let (int1, int2) = self.routing.pins(InterruptSignal::Click);
+        if int1 && !matches!(interrupt, Some((InterruptPin::Int1, _))) {
+            self.route(InterruptSignal::Click, InterruptPin::Int1, false)
+                .await?;
+        }
+        if int2 && !matches!(interrupt, Some((InterruptPin::Int2, _))) {
+            self.route(InterruptSignal::Click, InterruptPin::Int2, false)
+                .await?;
+        }
+
         match config.source {
             ClickSource::Double => {
                 self.enable_double_click(config.xyz_axes_enabled).await?;
@@ -480,25 +1729,32 @@ where
             }
         }
 
-        self.set_click_threshold(config.threshold).await?;
+        self.set_click_threshold(ths, latch).await?;
+        self.click_threshold_mg = Some(config.threshold_mg);
 
-        // NOTE: the numbers below depends on the OutputDataRate so changing the ODR affects double click times
-        self.set_click_time(config.time.limit, config.time.latency, config.time.window)
-            .await?;
+        // the click time depends on the OutputDataRate so it is recomputed whenever the ODR changes
+        if let Some((limit, latency, window)) = click_time {
+            self.set_click_time(limit, latency, window).await?;
+        }
+        self.click_time = Some(config.time);
 
-        match config.interrupt {
-            ClickInterupt::Int1(int_config) => {
-                self.enable_i1_click(true).await?;
-                self.int1_enable_click(true).await?;
-                self.set_int_polarity(int_config.active_low).await?;
-                self.enable_lir_int1(int_config.latch).await?;
-            }
-            _ => {} // do nothing (we don't support int2 yet)
+        if let Some((pin, int_config)) = interrupt {
+            self.write_int_polarity(int_config.active_low).await?;
+            self.route(InterruptSignal::Click, pin, true).await?;
         }
 
         Ok(())
     }
 
+    /// Click source,
+    /// `CLICK_SRC`: as
+    /// ClickEvent {active: `IA`, source: `SClick` / `DClick`, xyz: (`X`, `Y`, `Z`), sign: `Sign`},
+    /// reading also clears a latched click interrupt
+    pub async fn read_click_source(&mut self) -> Result<ClickEvent, Error<SpiError, PinError>> {
+        let reg = self.read_reg(Register::CLICK_SRC).await?;
+        Ok(ClickEvent::from(reg))
+    }
+
     /// Enable interrupt double-click on X,Y,Z axis,
     /// `CLICK_CFG`: `XD`, `YD`, `ZD`
     async fn enable_double_click(
@@ -526,32 +1782,451 @@ where
         Ok(())
     }
 
-    /// AOI-6D Interrupt mode,
-    /// `INTx_CFG`: `AOI`, `6D`
-    async fn int1_enable_click(&mut self, enable: bool) -> Result<(), Error<SpiError, PinError>> {
-        self.reg_xset_bits(Register::INT1_CFG, INT1_CFG_ENABLE_CLICK, enable)
+    /// Inertial interrupt generator configuration,
+    /// `INTx_CFG`: `AOI`, `6D`, `ZHIE`, `ZLIE`, `YHIE`, `YLIE`, `XHIE`, `XLIE`,
+    /// `INTx_THS`: `Ths`,
+    /// `INTx_DURATION`: `D`
+    pub async fn set_inertial_interrupt(
+        &mut self,
+        generator: InterruptGenerator,
+        config: &InertialInterruptConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.write_reg(generator.cfg(), config.cfg_bits()).await?;
+        self.write_reg(generator.ths(), config.threshold & THS_MASK)
+            .await?;
+        self.write_reg(generator.duration(), config.duration & D_MASK)
             .await?;
         Ok(())
     }
 
+    /// Interrupt generator 1 source,
+    /// `INT1_SRC`: as
+    /// InterruptSource {ia: `IA`, xyz_low: (`XL`, `YL`, `ZL`), xyz_high: (`XH`, `YH`, `ZH`)},
+    /// reading also clears a latched interrupt (`LIR_INT1`)
+    pub async fn get_int1_src(&mut self) -> Result<InterruptSource, Error<SpiError, PinError>> {
+        self.get_int_src(InterruptGenerator::Int1).await
+    }
+
+    /// Interrupt generator 2 source,
+    /// `INT2_SRC`: as
+    /// InterruptSource {ia: `IA`, xyz_low: (`XL`, `YL`, `ZL`), xyz_high: (`XH`, `YH`, `ZH`)},
+    /// reading also clears a latched interrupt (`LIR_INT2`)
+    pub async fn get_int2_src(&mut self) -> Result<InterruptSource, Error<SpiError, PinError>> {
+        self.get_int_src(InterruptGenerator::Int2).await
+    }
+
+    /// Interrupt generator source,
+    /// `INTx_SRC`
+    pub async fn get_int_src(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<InterruptSource, Error<SpiError, PinError>> {
+        let reg = self.read_reg(generator.src()).await?;
+        Ok(InterruptSource::from(reg))
+    }
+
+    /// 6D/4D orientation detection,
+    /// `CTRL_REG2`: `HP_IAx` cleared, the orientation is taken from unfiltered data,
+    /// `INTx_CFG`: `AOI`, `6D` as `Position6D` on all axes,
+    /// `INTx_THS`: `Ths` derived from the threshold angle and the current scale,
+    /// `CTRL_REG5`: `D4D_INTx`
+    pub async fn enable_orientation_detection(
+        &mut self,
+        config: &OrientationConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let threshold = sin_mg(config.threshold_deg) / self.scale.ths_lsb_mg() as u32;
+        let int_config = InertialInterruptConfig {
+            mode: Aoi6d::Position6D,
+            xyz_low_enabled: (true, true, true),
+            xyz_high_enabled: (true, true, true),
+            threshold: threshold.min(THS_MASK as u32) as u8,
+            duration: config.duration,
+        };
+
+        self.set_hp_filtered(config.generator, false).await?;
+        self.set_inertial_interrupt(config.generator, &int_config)
+            .await?;
+        self.set_4d(config.generator, config.four_d).await?;
+        Ok(())
+    }
+
+    /// Current orientation,
+    /// decoded from `INTx_SRC`
+    pub async fn get_orientation(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<Option<Orientation>, Error<SpiError, PinError>> {
+        let src = self.get_int_src(generator).await?;
+        Ok(Orientation::from_source(&src))
+    }
+
+    /// Free-fall detection,
+    /// `CTRL_REG2`: `HP_IAx` cleared, free fall is detected on unfiltered data,
+    /// `INTx_CFG`: `AOI`, `6D` as `And` with `XLIE`, `YLIE`, `ZLIE`,
+    /// `INTx_THS`, `INTx_DURATION` derived from the current scale and output data rate,
+    /// `CTRL_REG5`: `LIR_INTx`,
+    /// and the interrupt generator routed to the chosen pin
+    pub async fn enable_free_fall(
+        &mut self,
+        config: &FreeFallConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.check_lir(InterruptSignal::from(config.generator), config.latch)?;
+        let int_config = InertialInterruptConfig {
+            mode: Aoi6d::And,
+            xyz_low_enabled: (true, true, true),
+            xyz_high_enabled: (false, false, false),
+            threshold: self.ths_from_mg(config.threshold_mg)?,
+            duration: self.duration_from_ms(config.duration_ms)?,
+        };
+
+        self.set_hp_filtered(config.generator, false).await?;
+        self.set_inertial_interrupt(config.generator, &int_config)
+            .await?;
+        self.enable_lir(config.generator, config.latch).await?;
+        self.route_generator(config.generator, config.pin).await?;
+        Ok(())
+    }
+
+    /// Free-fall event,
+    /// `INTx_SRC`: `IA`, reading also clears a latched interrupt
+    pub async fn is_free_fall(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<bool, Error<SpiError, PinError>> {
+        let src = self.get_int_src(generator).await?;
+        Ok(src.ia)
+    }
+
+    /// Wake-up / motion detection,
+    /// `CTRL_REG2`: `HP_IAx`,
+    /// `INTx_CFG`: `AOI`, `6D` as `Or` with `XHIE`, `YHIE`, `ZHIE`,
+    /// `INTx_THS`, `INTx_DURATION` derived from the current scale and output data rate,
+    /// `CTRL_REG5`: `LIR_INTx`,
+    /// and the interrupt generator routed to the chosen pin.
+    /// `REFERENCE` is read to reset the high-pass filter before the interrupt is armed
+    pub async fn enable_motion_wake(
+        &mut self,
+        config: &MotionWakeConfig,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.check_lir(InterruptSignal::from(config.generator), config.latch)?;
+        let int_config = InertialInterruptConfig {
+            mode: Aoi6d::Or,
+            xyz_low_enabled: (false, false, false),
+            xyz_high_enabled: config.xyz_axes_enabled,
+            threshold: self.ths_from_mg(config.threshold_mg)?,
+            duration: self.duration_from_ms(config.duration_ms)?,
+        };
+
+        let hp_ia = match config.generator {
+            InterruptGenerator::Int1 => HP_IA1,
+            InterruptGenerator::Int2 => HP_IA2,
+        };
+        self.reg_set_bits(Register::CTRL_REG2, hp_ia).await?;
+        self.set_inertial_interrupt(config.generator, &int_config)
+            .await?;
+        self.enable_lir(config.generator, config.latch).await?;
+        self.get_ref().await?;
+
+        // discard anything latched while the filter was settling
+        self.get_int_src(config.generator).await?;
+        self.route_generator(config.generator, config.pin).await?;
+        Ok(())
+    }
+
+    /// `INTx_THS` value of a threshold in mg at the current scale
+    fn ths_from_mg(&self, mg: u16) -> Result<u8, Error<SpiError, PinError>> {
+        let lsb = self.scale.ths_lsb_mg() as u32;
+        let ths = (mg as u32 + lsb / 2) / lsb;
+        if ths > THS_MASK as u32 {
+            return Err(Error::OutOfRange);
+        }
+        Ok(ths as u8)
+    }
+
+    /// `INTx_DURATION` value of a duration in ms at the current output data rate
+    fn duration_from_ms(&self, ms: u16) -> Result<u8, Error<SpiError, PinError>> {
+        let cycles = self.odr_cycles_from_ms(ms)?;
+        if cycles > D_MASK as u32 {
+            return Err(Error::OutOfRange);
+        }
+        Ok(cycles as u8)
+    }
+
+    /// Number of ODR cycles in a duration in ms at the current output data rate
+    fn odr_cycles_from_ms(&self, ms: u16) -> Result<u32, Error<SpiError, PinError>> {
+        let hz = self.odr.hz(self.mode);
+        if hz == 0 && ms > 0 {
+            // no time passes in power-down mode
+            return Err(Error::OutOfRange);
+        }
+        Ok(odr_cycles(ms, hz))
+    }
+
+    /// `TIME_LIMIT`, `TIME_LATENCY`, `TIME_WINDOW` values of the click timing,
+    /// `None` in power-down mode where no clicks are detected
+    fn click_time_cycles(
+        odr: OutputDataRate,
+        mode: OperatingMode,
+        time: &ClickTime,
+    ) -> Result<Option<ClickTimeCycles>, Error<SpiError, PinError>> {
+        click_time_cycles(odr, mode, time).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Route an interrupt generator to an interrupt pin,
+    /// `CTRL_REG3`: `I1_IA1`, `I1_IA2` or `CTRL_REG6`: `I2_IA1`, `I2_IA2`
+    async fn route_generator(
+        &mut self,
+        generator: InterruptGenerator,
+        pin: InterruptPin,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.route(InterruptSignal::from(generator), pin, true)
+            .await
+    }
+
+    /// Route a signal to an interrupt pin,
+    /// `CTRL_REG3` for `INT1`, `CTRL_REG6` for `INT2`
+    pub async fn route(
+        &mut self,
+        signal: InterruptSignal,
+        pin: InterruptPin,
+        enable: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let mut routing = self.routing;
+        if !routing.set(signal, pin, enable) {
+            return Err(Error::RoutingConflict);
+        }
+
+        match pin {
+            InterruptPin::Int1 => self.set_int1_routing(routing.int1).await?,
+            InterruptPin::Int2 => self.set_int2_routing(routing.int2).await?,
+        }
+        Ok(())
+    }
+
+    /// Polarity requested for a signal,
+    /// fails if other routed signals rely on the opposite polarity
+    fn check_polarity(
+        &self,
+        signal: Option<InterruptSignal>,
+        active_low: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        if self.routing.active_low != active_low && self.routing.others_routed(signal) {
+            return Err(Error::RoutingConflict);
+        }
+        Ok(())
+    }
+
+    /// Latching requested for a signal,
+    /// fails if the signal is already routed with the opposite latching,
+    /// unroute it first to change its latching
+    fn check_lir(
+        &self,
+        signal: InterruptSignal,
+        latch: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        match self.routing.latch(signal) {
+            Some(latched) if latched != latch && self.routing.is_routed(signal) => {
+                Err(Error::RoutingConflict)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Latch interrupt request on INTx_SRC, cleared by reading INTx_SRC,
+    /// fails with `RoutingConflict` instead of changing the latching of a generator already routed,
+    /// `CTRL_REG5`: `LIR_INTx`
+    pub async fn enable_lir(
+        &mut self,
+        generator: InterruptGenerator,
+        latch: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.check_lir(InterruptSignal::from(generator), latch)?;
+        match generator {
+            InterruptGenerator::Int1 => self.enable_lir_int1(latch).await,
+            InterruptGenerator::Int2 => self.enable_lir_int2(latch).await,
+        }
+    }
+
+    /// High-pass filtered data to an interrupt generator,
+    /// `CTRL_REG2`: `HP_IAx`
+    async fn set_hp_filtered(
+        &mut self,
+        generator: InterruptGenerator,
+        enable: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let hp_ia = match generator {
+            InterruptGenerator::Int1 => HP_IA1,
+            InterruptGenerator::Int2 => HP_IA2,
+        };
+        self.reg_xset_bits(Register::CTRL_REG2, hp_ia, enable).await
+    }
+
+    /// 4D detection (the Z axis is ignored) when 6D is enabled in INTx_CFG,
+    /// `CTRL_REG5`: `D4D_INTx`
+    pub async fn set_4d(
+        &mut self,
+        generator: InterruptGenerator,
+        enable: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let d4d = match generator {
+            InterruptGenerator::Int1 => D4D_INT1,
+            InterruptGenerator::Int2 => D4D_INT2,
+        };
+        self.reg_xset_bits(Register::CTRL_REG5, d4d, enable).await
+    }
+
+    /// Click time in ms,
+    /// `TIME_LIMIT`, `TIME_LATENCY`, `TIME_WINDOW` derived from the current output data rate
+    /// and recomputed whenever it changes
+    pub async fn set_click_time_ms(
+        &mut self,
+        time: ClickTime,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let click_time = Self::click_time_cycles(self.odr, self.mode, &time)?;
+        if let Some((limit, latency, window)) = click_time {
+            self.set_click_time(limit, latency, window).await?;
+        }
+        self.click_time = Some(time);
+        Ok(())
+    }
+
+    /// Cached click time at another output data rate or operating mode
+    fn click_time_cycles_at(
+        &self,
+        odr: OutputDataRate,
+        mode: OperatingMode,
+    ) -> Result<Option<ClickTimeCycles>, Error<SpiError, PinError>> {
+        match &self.click_time {
+            Some(time) => Self::click_time_cycles(odr, mode, time),
+            None => Ok(None),
+        }
+    }
+
     /// Click time,
+    /// `TIME_LIMIT`: `TLI`, `TIME_LATENCY`: `TLA`, `TIME_WINDOW`: `TW`
     async fn set_click_time(
         &mut self,
         limit: u8,
         latency: u8,
         window: u8,
     ) -> Result<(), Error<SpiError, PinError>> {
-        self.write_reg(Register::TIME_LIMIT, limit).await?;
+        self.write_reg(Register::TIME_LIMIT, limit & TLI_MASK)
+            .await?;
         self.write_reg(Register::TIME_LATENCY, latency).await?;
         self.write_reg(Register::TIME_WINDOW, window).await?;
         Ok(())
     }
 
-    /// `CLICK` interrupt on `INT1` pin,
-    /// `CTRL_REG3`: `I1_CLICK`
-    async fn enable_i1_click(&mut self, enable: bool) -> Result<(), Error<SpiError, PinError>> {
-        self.reg_xset_bits(Register::CTRL_REG3, I1_CLICK, enable)
+    /// Signals routed to the `INT1` pin,
+    /// `CTRL_REG3`: `I1_CLICK`, `I1_IA1`, `I1_IA2`, `I1_ZYXDA`, `I1_WTM`, `I1_OVERRUN`
+    async fn set_int1_routing(
+        &mut self,
+        routing: Int1Routing,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.write_reg(Register::CTRL_REG3, routing.bits()).await?;
+        self.routing.int1 = routing;
+        Ok(())
+    }
+
+    /// Signals routed to the `INT2` pin, the pin polarity is left unchanged,
+    /// `CTRL_REG6`: `I2_CLICK`, `I2_IA1`, `I2_IA2`, `I2_BOOT`, `I2_ACT`
+    async fn set_int2_routing(
+        &mut self,
+        routing: Int2Routing,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.modify_reg(Register::CTRL_REG6, |v| (v & INT_POLARITY) | routing.bits())
+            .await?;
+        self.routing.int2 = routing;
+        Ok(())
+    }
+
+    /// Interrupt routing of both pins as configured through the driver
+    pub fn get_routing(&self) -> InterruptRouting {
+        self.routing
+    }
+
+    /// Active level and latching of a pin,
+    /// `INT_POLARITY` and the `LIR_Click`, `LIR_INTx` bits of the interrupt generators routed to the pin
+    fn get_pin_trigger(&self, pin: InterruptPin) -> (bool, bool) {
+        let r = self.routing;
+        let (click, ia1, ia2) = match pin {
+            InterruptPin::Int1 => (r.int1.click, r.int1.ia1, r.int1.ia2),
+            InterruptPin::Int2 => (r.int2.click, r.int2.ia1, r.int2.ia2),
+        };
+        let latched = (click && r.latch_click) || (ia1 && r.latch_ia1) || (ia2 && r.latch_ia2);
+        (r.active_low, latched)
+    }
+
+    /// Interrupt events of a pin,
+    /// reads only the source registers of the signals routed to that pin
+    /// (`CLICK_SRC`, `INT1_SRC`, `INT2_SRC`, `STATUS_REG`, `FIFO_SRC_REG`),
+    /// which also clears latched interrupts
+    pub async fn read_interrupt_events(
+        &mut self,
+        pin: InterruptPin,
+    ) -> Result<InterruptEvents, Error<SpiError, PinError>> {
+        let (click, ia1, ia2) = match pin {
+            InterruptPin::Int1 => {
+                let r = self.routing.int1;
+                (r.click, r.ia1, r.ia2)
+            }
+            InterruptPin::Int2 => {
+                let r = self.routing.int2;
+                (r.click, r.ia1, r.ia2)
+            }
+        };
+
+        let mut events = InterruptEvents::default();
+        if click {
+            let click = self.read_click_source().await?;
+            if click.active {
+                events.click = Some(click);
+            }
+        }
+        if ia1 {
+            let src = self.get_int1_src().await?;
+            if src.ia {
+                events.ia1 = Some(src);
+            }
+        }
+        if ia2 {
+            let src = self.get_int2_src().await?;
+            if src.ia {
+                events.ia2 = Some(src);
+            }
+        }
+
+        match pin {
+            InterruptPin::Int1 => {
+                let r = self.routing.int1;
+                if r.data_ready {
+                    events.data_ready = self.get_status().await?.zyxda;
+                }
+                if r.watermark || r.overrun {
+                    let fifo = self.read_reg(Register::FIFO_SRC_REG).await?;
+                    events.watermark = r.watermark && (fifo & WTM) != 0;
+                    events.overrun = r.overrun && (fifo & OVRN_FIFO) != 0;
+                }
+            }
+            InterruptPin::Int2 => {
+                // activity has no source register
+                events.activity = self.routing.int2.activity
+                    && events.click.is_none()
+                    && events.ia1.is_none()
+                    && events.ia2.is_none();
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Latch interrupt request on INT2_SRC (35h),
+    /// with INT2_SRC (35h) register cleared by reading INT2_SRC (35h) itself,
+    /// `CTRL_REG5`: `LIR_INT2`
+    async fn enable_lir_int2(&mut self, latch: bool) -> Result<(), Error<SpiError, PinError>> {
+        self.reg_xset_bits(Register::CTRL_REG5, LIR_INT2, latch)
             .await?;
+        self.routing.latch_ia2 = latch;
         Ok(())
     }
 
@@ -561,6 +2236,7 @@ where
     async fn enable_lir_int1(&mut self, latch: bool) -> Result<(), Error<SpiError, PinError>> {
         self.reg_xset_bits(Register::CTRL_REG5, LIR_INT1, latch)
             .await?;
+        self.routing.latch_ia1 = latch;
         Ok(())
     }
 
@@ -581,21 +2257,65 @@ where
         Ok(())
     }
 
+    /// INT1/INT2 pin polarity, shared by both pins,
+    /// fails with `RoutingConflict` instead of changing the polarity of signals already routed,
+    /// `CTRL_REG6`: `INT_POLARITY`
+    pub async fn set_int_polarity(
+        &mut self,
+        active_low: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.check_polarity(None, active_low)?;
+        self.write_int_polarity(active_low).await
+    }
+
     /// INT1/INT2 pin polarity,
     /// `CTRL_REG6`: `INT_POLARITY`
-    async fn set_int_polarity(
+    async fn write_int_polarity(
         &mut self,
         active_low: bool,
     ) -> Result<(), Error<SpiError, PinError>> {
         self.reg_xset_bits(Register::CTRL_REG6, INT_POLARITY, active_low)
             .await?;
+        self.routing.active_low = active_low;
         Ok(())
     }
 
-    /// Click threshold,
-    /// `CLICK_THS`: `Ths`
-    async fn set_click_threshold(&mut self, ths: u8) -> Result<(), Error<SpiError, PinError>> {
-        self.write_reg(Register::CLICK_THS, ths & THS_MASK).await?;
+    /// Click threshold in mg, kept across scale changes,
+    /// `CLICK_THS`: `Ths` derived from the current scale
+    pub async fn set_click_threshold_mg(
+        &mut self,
+        mg: u16,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let ths = Self::click_ths_from_mg(self.scale, mg)?;
+        self.set_click_threshold(ths, self.routing.latch_click)
+            .await?;
+        self.click_threshold_mg = Some(mg);
+        Ok(())
+    }
+
+    /// `CLICK_THS` value of a threshold in mg at a scale
+    fn click_ths_from_mg(fs: FullScaleSelection, mg: u16) -> Result<u8, Error<SpiError, PinError>> {
+        click_ths(fs, mg).map_err(|_| Error::OutOfRange)
+    }
+
+    /// Click threshold and latching,
+    /// `CLICK_THS`: `Ths`, `LIR_Click`
+    async fn set_click_threshold(
+        &mut self,
+        ths: u8,
+        latch: bool,
+    ) -> Result<(), Error<SpiError, PinError>> {
+        let lir = if latch { LIR_Click } else { 0 };
+        self.write_reg(Register::CLICK_THS, (ths & THS_MASK) | lir)
+            .await?;
+        self.routing.latch_click = latch;
+        Ok(())
+    }
+
+    /// Clear a latched click interrupt,
+    /// `CLICK_SRC` is read and discarded
+    pub async fn clear_click(&mut self) -> Result<(), Error<SpiError, PinError>> {
+        self.read_click_source().await?;
         Ok(())
     }
 
@@ -643,6 +2363,18 @@ where
         result
     }
 
+    async fn write_regs(
+        &mut self,
+        register: Register,
+        data: &[u8],
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.chip_select()?;
+        let request = 0b0100_0000 | register.addr(); // set the register increment bit
+        let result = self.write_then_write_all(request, data).await;
+        self.chip_deselect()?;
+        result
+    }
+
     async fn read_regs(
         &mut self,
         register: Register,
@@ -688,6 +2420,16 @@ where
         Ok(())
     }
 
+    async fn write_then_write_all(
+        &mut self,
+        request: u8,
+        data: &[u8],
+    ) -> Result<(), Error<SpiError, PinError>> {
+        self.spi.write(&[request]).await?;
+        self.spi.write(data).await?;
+        Ok(())
+    }
+
     fn chip_select(&mut self) -> Result<(), Error<SpiError, PinError>> {
         self.cs.set_low().map_err(Error::Pin)
     }
@@ -696,3 +2438,228 @@ where
         self.cs.set_high().map_err(Error::Pin)
     }
 }
+
+impl<SPI, SpiError, CS, PinError, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2>
+where
+    SPI: FullDuplex<u8, Error = SpiError>,
+    CS: OutputPin<Error = PinError>,
+    INT1: InterruptInput,
+{
+    /// Wait for the INT1 pin to become active and decode its events,
+    /// see `read_interrupt_events`
+    pub async fn wait_for_int1_event(
+        &mut self,
+    ) -> Result<InterruptEvents, Error<SpiError, PinError>> {
+        let trigger = self.get_pin_trigger(InterruptPin::Int1);
+        wait_for_trigger(&mut self.int1, trigger).await;
+        self.read_interrupt_events(InterruptPin::Int1).await
+    }
+}
+
+impl<SPI, SpiError, CS, PinError, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2>
+where
+    SPI: FullDuplex<u8, Error = SpiError>,
+    CS: OutputPin<Error = PinError>,
+    INT2: InputPin,
+{
+    /// Auto low-power inactive state, read from the driver-owned INT2 pin,
+    /// the pin is asserted while the device sleeps when `I2_ACT` is set in `CTRL_REG6`,
+    /// the active level is taken from the configured `INT_POLARITY`,
+    /// fails with `RoutingConflict` if the activity signal is not routed to INT2
+    pub fn is_inactive(&self) -> Result<bool, Error<SpiError, PinError>> {
+        if !self.routing.int2.activity {
+            return Err(Error::RoutingConflict);
+        }
+        let high = self.int2.is_high().map_err(|_| Error::IntPin)?;
+        Ok(high != self.routing.active_low)
+    }
+}
+
+impl<SPI, SpiError, CS, PinError, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2>
+where
+    SPI: FullDuplex<u8, Error = SpiError>,
+    CS: OutputPin<Error = PinError>,
+    INT2: InterruptInput,
+{
+    /// Wait for the INT2 pin to become active and decode its events,
+    /// see `read_interrupt_events`
+    pub async fn wait_for_int2_event(
+        &mut self,
+    ) -> Result<InterruptEvents, Error<SpiError, PinError>> {
+        let trigger = self.get_pin_trigger(InterruptPin::Int2);
+        wait_for_trigger(&mut self.int2, trigger).await;
+        self.read_interrupt_events(InterruptPin::Int2).await
+    }
+}
+
+impl<SPI, SpiError, CS, PinError, INT1, INT2> Lis2dh12<SPI, CS, INT1, INT2>
+where
+    SPI: FullDuplex<u8, Error = SpiError>,
+    CS: OutputPin<Error = PinError>,
+    INT1: InterruptInput,
+    INT2: InterruptInput,
+{
+    /// Wait for the owned pins until at least one becomes active and decode the events
+    /// of every pin that did, as (INT1, INT2) with `None` for a pin that did not fire,
+    /// both pins are polled each time so simultaneous edges are not lost,
+    /// a `NoPin` never becomes active, see `read_interrupt_events`
+    pub async fn wait_for_event(
+        &mut self,
+    ) -> Result<(Option<InterruptEvents>, Option<InterruptEvents>), Error<SpiError, PinError>> {
+        let trigger1 = self.get_pin_trigger(InterruptPin::Int1);
+        let trigger2 = self.get_pin_trigger(InterruptPin::Int2);
+        let (int1, int2) = Select {
+            int1: wait_for_trigger(&mut self.int1, trigger1),
+            int2: wait_for_trigger(&mut self.int2, trigger2),
+        }
+        .await;
+
+        let events1 = if int1 {
+            Some(self.read_interrupt_events(InterruptPin::Int1).await?)
+        } else {
+            None
+        };
+        let events2 = if int2 {
+            Some(self.read_interrupt_events(InterruptPin::Int2).await?)
+        } else {
+            None
+        };
+        Ok((events1, events2))
+    }
+
+    /// Wait for motion on the owned pin the interrupt generator is routed to (INT1 first),
+    /// returns the decoded `INTx_SRC` (which also clears a latched interrupt),
+    /// fails with `RoutingConflict` if the generator is not routed to any pin
+    pub async fn await_motion(
+        &mut self,
+        generator: InterruptGenerator,
+    ) -> Result<InterruptSource, Error<SpiError, PinError>> {
+        let pin = match self.routing.pins(InterruptSignal::from(generator)) {
+            (true, _) => InterruptPin::Int1,
+            (false, true) => InterruptPin::Int2,
+            (false, false) => return Err(Error::RoutingConflict),
+        };
+        let trigger = self.get_pin_trigger(pin);
+        loop {
+            match pin {
+                InterruptPin::Int1 => wait_for_trigger(&mut self.int1, trigger).await,
+                InterruptPin::Int2 => wait_for_trigger(&mut self.int2, trigger).await,
+            }
+
+            let src = self.get_int_src(generator).await?;
+            if src.ia {
+                return Ok(src);
+            }
+        }
+    }
+}
+
+// wait for the active level of a latched interrupt or the active edge of a pulsed one
+async fn wait_for_trigger<P: InterruptInput>(pin: &mut P, (active_low, latched): (bool, bool)) {
+    match (active_low, latched) {
+        (true, true) => pin.wait_for_low().await,
+        (false, true) => pin.wait_for_high().await,
+        (true, false) => pin.wait_for_falling_edge().await,
+        (false, false) => pin.wait_for_rising_edge().await,
+    }
+}
+
+// polls both futures and completes with (INT1, INT2) done once at least one of them is done
+struct Select<A, B> {
+    int1: A,
+    int2: B,
+}
+
+impl<A, B> Future for Select<A, B>
+where
+    A: Future<Output = ()>,
+    B: Future<Output = ()>,
+{
+    type Output = (bool, bool);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // safety: the fields are never moved out of the pinned struct
+        let this = unsafe { self.get_unchecked_mut() };
+        let int1 = unsafe { Pin::new_unchecked(&mut this.int1) }
+            .poll(cx)
+            .is_ready();
+        let int2 = unsafe { Pin::new_unchecked(&mut this.int2) }
+            .poll(cx)
+            .is_ready();
+        if int1 || int2 {
+            Poll::Ready((int1, int2))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl WaitForHigh for NoPin {
+    type Future<'a> = Pending<()>;
+
+    fn wait_for_high(&mut self) -> Self::Future<'_> {
+        pending()
+    }
+}
+
+impl WaitForLow for NoPin {
+    type Future<'a> = Pending<()>;
+
+    fn wait_for_low(&mut self) -> Self::Future<'_> {
+        pending()
+    }
+}
+
+impl WaitForRisingEdge for NoPin {
+    type Future<'a> = Pending<()>;
+
+    fn wait_for_rising_edge(&mut self) -> Self::Future<'_> {
+        pending()
+    }
+}
+
+impl WaitForFallingEdge for NoPin {
+    type Future<'a> = Pending<()>;
+
+    fn wait_for_falling_edge(&mut self) -> Self::Future<'_> {
+        pending()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn click(threshold_mg: u16) -> Config {
+        Config::default().click(Some(ClickDetection {
+            threshold_mg,
+            ..ClickDetection::default()
+        }))
+    }
+
+    #[test]
+    fn checked_registers_of_valid_config() {
+        let config = click(1500);
+        let regs = checked_registers::<(), ()>(&config);
+        assert_eq!(regs.ok(), config.registers().ok());
+    }
+
+    #[test]
+    fn checked_registers_rejects_invalid_config() {
+        let config = Config::default().temperature(true).bdu(false);
+        assert!(matches!(
+            checked_registers::<(), ()>(&config),
+            Err(Error::InvalidConfig(ConfigError::TemperatureRequiresBdu))
+        ));
+    }
+
+    #[test]
+    fn checked_registers_rejects_click_out_of_range() {
+        // the click threshold is limited to 127 LSB of 16 mg at ±2g
+        let config = click(2500);
+        assert!(matches!(
+            checked_registers::<(), ()>(&config),
+            Err(Error::InvalidConfig(ConfigError::ClickOutOfRange))
+        ));
+    }
+}
@@ -1,10 +1,13 @@
 #![allow(non_upper_case_globals)]
 
+use num_derive::FromPrimitive;
+
 /// Register mapping
 #[allow(dead_code)]
 #[allow(non_camel_case_types)]
 #[allow(clippy::upper_case_acronyms)]
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Register {
     STATUS_REG_AUX = 0x07,
     OUT_TEMP_L = 0x0C,
@@ -47,7 +50,7 @@ pub enum Register {
 }
 
 /// AOI-6D Interrupt mode
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, FromPrimitive)]
 pub enum Aoi6d {
     /// OR combination of interrupt events
     Or = 0b00,
@@ -59,16 +62,235 @@ pub enum Aoi6d {
     Position6D = 0b11,
 }
 
+// === FIFO_CTRL_REG (2Eh) ===
+
+pub const FM_MASK: u8 = 0b1100_0000;
+pub const TR: u8 = 0b0010_0000;
+pub const FTH_MASK: u8 = 0b0001_1111;
+
+/// FIFO mode
+#[derive(Copy, Clone, FromPrimitive)]
+pub enum FifoMode {
+    /// Bypass mode, the FIFO is not used
+    Bypass = 0b00,
+    /// FIFO mode, stops collecting data when full
+    Fifo = 0b01,
+    /// Stream mode, the oldest samples are discarded when full
+    Stream = 0b10,
+    /// Stream mode until the trigger event, then FIFO mode
+    StreamToFifo = 0b11,
+}
+
+// === FIFO_SRC_REG (2Fh) ===
+
+pub const WTM: u8 = 0b1000_0000;
+pub const OVRN_FIFO: u8 = 0b0100_0000;
+pub const EMPTY: u8 = 0b0010_0000;
+pub const FSS_MASK: u8 = 0b0001_1111;
+
 // === INT1_CFG (30h), INT2_CFG (34h) ===
 
 pub const AOI_6D_MASK: u8 = 0b1100_0000;
-
-pub const INT1_CFG_ENABLE_CLICK: u8 = 0b0011_1111;
+pub const ZHIE: u8 = 0b0010_0000;
+pub const ZLIE: u8 = 0b0001_0000;
+pub const YHIE: u8 = 0b0000_1000;
+pub const YLIE: u8 = 0b0000_0100;
+pub const XHIE: u8 = 0b0000_0010;
+pub const XLIE: u8 = 0b0000_0001;
 
 impl Register {
     pub fn addr(self) -> u8 {
         self as u8
     }
+
+    /// Datasheet name of the register
+    pub fn name(self) -> &'static str {
+        match self {
+            Register::STATUS_REG_AUX => "STATUS_REG_AUX",
+            Register::OUT_TEMP_L => "OUT_TEMP_L",
+            Register::OUT_TEMP_H => "OUT_TEMP_H",
+            Register::WHO_AM_I => "WHO_AM_I",
+            Register::CTRL_REG0 => "CTRL_REG0",
+            Register::TEMP_CFG_REG => "TEMP_CFG_REG",
+            Register::CTRL_REG1 => "CTRL_REG1",
+            Register::CTRL_REG2 => "CTRL_REG2",
+            Register::CTRL_REG3 => "CTRL_REG3",
+            Register::CTRL_REG4 => "CTRL_REG4",
+            Register::CTRL_REG5 => "CTRL_REG5",
+            Register::CTRL_REG6 => "CTRL_REG6",
+            Register::REFERENCE => "REFERENCE",
+            Register::STATUS_REG => "STATUS_REG",
+            Register::OUT_X_L => "OUT_X_L",
+            Register::OUT_X_H => "OUT_X_H",
+            Register::OUT_Y_L => "OUT_Y_L",
+            Register::OUT_Y_H => "OUT_Y_H",
+            Register::OUT_Z_L => "OUT_Z_L",
+            Register::OUT_Z_H => "OUT_Z_H",
+            Register::FIFO_CTRL_REG => "FIFO_CTRL_REG",
+            Register::FIFO_SRC_REG => "FIFO_SRC_REG",
+            Register::INT1_CFG => "INT1_CFG",
+            Register::INT1_SRC => "INT1_SRC",
+            Register::INT1_THS => "INT1_THS",
+            Register::INT1_DURATION => "INT1_DURATION",
+            Register::INT2_CFG => "INT2_CFG",
+            Register::INT2_SRC => "INT2_SRC",
+            Register::INT2_THS => "INT2_THS",
+            Register::INT2_DURATION => "INT2_DURATION",
+            Register::CLICK_CFG => "CLICK_CFG",
+            Register::CLICK_SRC => "CLICK_SRC",
+            Register::CLICK_THS => "CLICK_THS",
+            Register::TIME_LIMIT => "TIME_LIMIT",
+            Register::TIME_LATENCY => "TIME_LATENCY",
+            Register::TIME_WINDOW => "TIME_WINDOW",
+            Register::ACT_THS => "ACT_THS",
+            Register::ACT_DUR => "ACT_DUR",
+        }
+    }
+
+    /// Reading the register has side effects:
+    /// `REFERENCE` resets the high-pass filter, the source registers clear latched interrupts
+    /// and the output registers release the block data update
+    pub fn read_has_side_effects(self) -> bool {
+        matches!(
+            self,
+            Register::REFERENCE
+                | Register::INT1_SRC
+                | Register::INT2_SRC
+                | Register::CLICK_SRC
+                | Register::OUT_TEMP_L
+                | Register::OUT_TEMP_H
+                | Register::OUT_X_L
+                | Register::OUT_X_H
+                | Register::OUT_Y_L
+                | Register::OUT_Y_H
+                | Register::OUT_Z_L
+                | Register::OUT_Z_H
+        )
+    }
+}
+
+/// All mapped registers in address order
+pub const REGISTERS: [Register; 38] = [
+    Register::STATUS_REG_AUX,
+    Register::OUT_TEMP_L,
+    Register::OUT_TEMP_H,
+    Register::WHO_AM_I,
+    Register::CTRL_REG0,
+    Register::TEMP_CFG_REG,
+    Register::CTRL_REG1,
+    Register::CTRL_REG2,
+    Register::CTRL_REG3,
+    Register::CTRL_REG4,
+    Register::CTRL_REG5,
+    Register::CTRL_REG6,
+    Register::REFERENCE,
+    Register::STATUS_REG,
+    Register::OUT_X_L,
+    Register::OUT_X_H,
+    Register::OUT_Y_L,
+    Register::OUT_Y_H,
+    Register::OUT_Z_L,
+    Register::OUT_Z_H,
+    Register::FIFO_CTRL_REG,
+    Register::FIFO_SRC_REG,
+    Register::INT1_CFG,
+    Register::INT1_SRC,
+    Register::INT1_THS,
+    Register::INT1_DURATION,
+    Register::INT2_CFG,
+    Register::INT2_SRC,
+    Register::INT2_THS,
+    Register::INT2_DURATION,
+    Register::CLICK_CFG,
+    Register::CLICK_SRC,
+    Register::CLICK_THS,
+    Register::TIME_LIMIT,
+    Register::TIME_LATENCY,
+    Register::TIME_WINDOW,
+    Register::ACT_THS,
+    Register::ACT_DUR,
+];
+
+/// Datasheet reset values of the writable registers,
+/// `CTRL_REG1` comes first so that the device is powered down while the rest is written
+pub const RESET_VALUES: [(Register, u8); 23] = [
+    (Register::CTRL_REG1, 0x07),
+    (Register::CTRL_REG0, CTRL_REG0_FIXED),
+    (Register::TEMP_CFG_REG, 0x00),
+    (Register::CTRL_REG2, 0x00),
+    (Register::CTRL_REG3, 0x00),
+    (Register::CTRL_REG4, 0x00),
+    (Register::CTRL_REG5, 0x00),
+    (Register::CTRL_REG6, 0x00),
+    (Register::REFERENCE, 0x00),
+    (Register::FIFO_CTRL_REG, 0x00),
+    (Register::INT1_CFG, 0x00),
+    (Register::INT1_THS, 0x00),
+    (Register::INT1_DURATION, 0x00),
+    (Register::INT2_CFG, 0x00),
+    (Register::INT2_THS, 0x00),
+    (Register::INT2_DURATION, 0x00),
+    (Register::CLICK_CFG, 0x00),
+    (Register::CLICK_THS, 0x00),
+    (Register::TIME_LIMIT, 0x00),
+    (Register::TIME_LATENCY, 0x00),
+    (Register::TIME_WINDOW, 0x00),
+    (Register::ACT_THS, 0x00),
+    (Register::ACT_DUR, 0x00),
+];
+
+/// Inertial interrupt generator
+#[derive(Copy, Clone)]
+pub enum InterruptGenerator {
+    /// Interrupt generator 1 (`INT1_CFG`, `INT1_SRC`, `INT1_THS`, `INT1_DURATION`)
+    Int1,
+    /// Interrupt generator 2 (`INT2_CFG`, `INT2_SRC`, `INT2_THS`, `INT2_DURATION`)
+    Int2,
+}
+
+/// Accelerometer axis
+#[derive(Copy, Clone)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Interrupt pin
+#[derive(Copy, Clone)]
+pub enum InterruptPin {
+    Int1,
+    Int2,
+}
+
+impl InterruptGenerator {
+    pub fn cfg(self) -> Register {
+        match self {
+            InterruptGenerator::Int1 => Register::INT1_CFG,
+            InterruptGenerator::Int2 => Register::INT2_CFG,
+        }
+    }
+
+    pub fn src(self) -> Register {
+        match self {
+            InterruptGenerator::Int1 => Register::INT1_SRC,
+            InterruptGenerator::Int2 => Register::INT2_SRC,
+        }
+    }
+
+    pub fn ths(self) -> Register {
+        match self {
+            InterruptGenerator::Int1 => Register::INT1_THS,
+            InterruptGenerator::Int2 => Register::INT2_THS,
+        }
+    }
+
+    pub fn duration(self) -> Register {
+        match self {
+            InterruptGenerator::Int1 => Register::INT1_DURATION,
+            InterruptGenerator::Int2 => Register::INT2_DURATION,
+        }
+    }
 }
 
 // === ACT_THS (3Eh) ===
@@ -93,10 +315,46 @@ pub const I2_BOOT: u8 = 0b0001_0000;
 pub const I2_ACT: u8 = 0b0000_1000;
 pub const INT_POLARITY: u8 = 0b0000_0010;
 
+// === INT1_SRC (31h), INT2_SRC (35h) ===
+
+pub const IA: u8 = 0b0100_0000;
+pub const ZH: u8 = 0b0010_0000;
+pub const ZL: u8 = 0b0001_0000;
+pub const YH: u8 = 0b0000_1000;
+pub const YL: u8 = 0b0000_0100;
+pub const XH: u8 = 0b0000_0010;
+pub const XL: u8 = 0b0000_0001;
+
+/// Interrupt source structure,
+/// decoded from INT1_SRC, INT2_SRC registers
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InterruptSource {
+    /// IA bit, one or more interrupts have been generated
+    pub ia: bool,
+    /// (XL, YL, ZL) bits, low event
+    pub xyz_low: (bool, bool, bool),
+    /// (XH, YH, ZH) bits, high event
+    pub xyz_high: (bool, bool, bool),
+}
+
+impl From<u8> for InterruptSource {
+    fn from(reg: u8) -> Self {
+        Self {
+            ia: (reg & IA) != 0,
+            xyz_low: ((reg & XL) != 0, (reg & YL) != 0, (reg & ZL) != 0),
+            xyz_high: ((reg & XH) != 0, (reg & YH) != 0, (reg & ZH) != 0),
+        }
+    }
+}
+
 // === INT1_THS (32h), INT2_THS (36h) ===
 
 pub const THS_MASK: u8 = 0b0111_1111;
 
+// === INT1_DURATION (33h), INT2_DURATION (37h) ===
+
+pub const D_MASK: u8 = 0b0111_1111;
+
 // === CLICK_CFG (38h) ===
 
 pub const ZD: u8 = 0b0010_0000;
@@ -106,9 +364,26 @@ pub const YS: u8 = 0b0000_0100;
 pub const XD: u8 = 0b0000_0010;
 pub const XS: u8 = 0b0000_0001;
 
+// === CLICK_THS (3Ah) ===
+
+pub const LIR_Click: u8 = 0b1000_0000;
+
+// === CLICK_SRC (39h) ===
+
+// IA: 0b0100_0000 (same as INTx_SRC)
+pub const DClick: u8 = 0b0010_0000;
+pub const SClick: u8 = 0b0001_0000;
+pub const Sign: u8 = 0b0000_1000;
+pub const Z: u8 = 0b0000_0100;
+pub const Y: u8 = 0b0000_0010;
+pub const X: u8 = 0b0000_0001;
+
+// === TIME_LIMIT (3Bh) ===
+
+pub const TLI_MASK: u8 = 0b0111_1111;
+
 /// Output Data Rate
-#[derive(Copy, Clone)]
-#[cfg_attr(feature = "out_f32", derive(FromPrimitive))]
+#[derive(Copy, Clone, FromPrimitive)]
 pub enum OutputDataRate {
     /// Power-down mode
     PowerDown = 0b0000,
@@ -133,6 +408,27 @@ pub enum OutputDataRate {
     HighRate1 = 0b1001,
 }
 
+impl OutputDataRate {
+    /// Output data rate in Hz, `HighRate1` depends on the operating mode
+    pub fn hz(self, mode: OperatingMode) -> u32 {
+        match self {
+            OutputDataRate::PowerDown => 0,
+            OutputDataRate::Hz1 => 1,
+            OutputDataRate::Hz10 => 10,
+            OutputDataRate::Hz25 => 25,
+            OutputDataRate::Hz50 => 50,
+            OutputDataRate::Hz100 => 100,
+            OutputDataRate::Hz200 => 200,
+            OutputDataRate::Hz400 => 400,
+            OutputDataRate::HighRate0 => 1620,
+            OutputDataRate::HighRate1 => match mode {
+                OperatingMode::LowPower => 5376,
+                _ => 1344,
+            },
+        }
+    }
+}
+
 /// WHO_AM_I device identification register
 pub const DEVICE_ID: u8 = 0b0011_0011; // 51 (decimal)
 
@@ -146,10 +442,65 @@ pub const Xen: u8 = 0b0000_0001;
 
 pub const HR: u8 = 0b0000_1000;
 
+// === CTRL_REG0 (1Eh) ===
+
+pub const SDO_PU_DISC: u8 = 0b1000_0000;
+/// Bits 6:0 must always be written as `001_0000` for correct operation of the device
+pub const CTRL_REG0_FIXED: u8 = 0b0001_0000;
+
 // === TEMP_CFG_REG (1Fh) ===
 
 pub const TEMP_EN: u8 = 0b1100_0000;
 
+// === CTRL_REG2 (21h) ===
+
+pub const HPM_MASK: u8 = 0b1100_0000;
+pub const HPCF_MASK: u8 = 0b0011_0000;
+pub const FDS: u8 = 0b0000_1000;
+pub const HPCLICK: u8 = 0b0000_0100;
+pub const HP_IA2: u8 = 0b0000_0010;
+pub const HP_IA1: u8 = 0b0000_0001;
+
+/// High-pass filter mode
+#[derive(Copy, Clone, FromPrimitive)]
+pub enum HighPassMode {
+    /// Normal mode (reset by reading REFERENCE)
+    NormalWithReset = 0b00,
+    /// Reference signal for filtering
+    Reference = 0b01,
+    /// Normal mode
+    Normal = 0b10,
+    /// Autoreset on interrupt event
+    AutoresetOnInterrupt = 0b11,
+}
+
+/// High-pass filter cutoff frequency,
+/// relative to the output data rate
+#[derive(Copy, Clone, FromPrimitive)]
+pub enum HighPassCutoff {
+    /// ODR / 50
+    OdrDiv50 = 0b00,
+    /// ODR / 100
+    OdrDiv100 = 0b01,
+    /// ODR / 200
+    OdrDiv200 = 0b10,
+    /// ODR / 500
+    OdrDiv500 = 0b11,
+}
+
+impl HighPassCutoff {
+    /// Approximate cutoff frequency in Hz at an output data rate
+    pub fn hz(self, odr: OutputDataRate, mode: OperatingMode) -> f32 {
+        let divider = match self {
+            HighPassCutoff::OdrDiv50 => 50.0,
+            HighPassCutoff::OdrDiv100 => 100.0,
+            HighPassCutoff::OdrDiv200 => 200.0,
+            HighPassCutoff::OdrDiv500 => 500.0,
+        };
+        odr.hz(mode) as f32 / divider
+    }
+}
+
 // === CTRL_REG3 (22h) ===
 
 pub const I1_CLICK: u8 = 0b1000_0000;
@@ -162,10 +513,30 @@ pub const I1_OVERRUN: u8 = 0b0000_0010;
 // === CTRL_REG4 (23h) ===
 
 pub const BDU: u8 = 0b1000_0000;
+pub const BLE: u8 = 0b0100_0000;
 
 pub const FS_MASK: u8 = 0b0011_0000;
 
+pub const ST_MASK: u8 = 0b0000_0110;
+
+/// Self-test mode
 #[derive(Copy, Clone)]
+pub enum SelfTest {
+    /// Normal mode
+    Disabled = 0b00,
+    /// Self test 0
+    Test0 = 0b01,
+    /// Self test 1
+    Test1 = 0b10,
+}
+
+/// Minimum self-test output change in 10-bit LSB (normal mode, ±2g)
+pub const SELF_TEST_MIN: i16 = 17;
+
+/// Maximum self-test output change in 10-bit LSB (normal mode, ±2g)
+pub const SELF_TEST_MAX: i16 = 360;
+
+#[derive(Copy, Clone, FromPrimitive)]
 pub enum FullScaleSelection {
     PlusMinus2G = 0x00, // default
     PlusMinus4G = 0x01,
@@ -173,6 +544,18 @@ pub enum FullScaleSelection {
     PlusMinus16G = 0x03,
 }
 
+impl FullScaleSelection {
+    /// Milli-g per LSB of the `INTx_THS`, `CLICK_THS` and `ACT_THS` thresholds
+    pub fn ths_lsb_mg(self) -> u16 {
+        match self {
+            FullScaleSelection::PlusMinus2G => 16,
+            FullScaleSelection::PlusMinus4G => 32,
+            FullScaleSelection::PlusMinus8G => 62,
+            FullScaleSelection::PlusMinus16G => 186,
+        }
+    }
+}
+
 /// Data status structure,
 /// decoded from STATUS_REG register
 #[derive(Debug)]
@@ -188,6 +571,7 @@ pub struct DataStatus {
 }
 
 /// Operating mode
+#[derive(Copy, Clone)]
 pub enum OperatingMode {
     /// High-resolution mode (12-bit data output)
     HighResolution,